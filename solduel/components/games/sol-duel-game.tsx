@@ -238,7 +238,7 @@ export function SolDuelGame() {
     setIsLoading(true)
     try {
       // Join the game on blockchain
-      const tx = await sdk.joinGame(duel.roomId)
+      const { tx } = await sdk.joinStrategicDuel(duel.roomId)
       console.log('Joined game:', tx)
       
       // Set the current duel ID to start polling
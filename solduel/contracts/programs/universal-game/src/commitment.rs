@@ -0,0 +1,215 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use crate::constants::{DECK_CARDS, DECK_PROOF_DEPTH};
+use crate::state::GameMove;
+
+/// Domain tag prefixed to every move commitment
+pub const MOVE_COMMITMENT_DOMAIN: &[u8] = b"solduel:move:v1";
+
+/// Domain tag prefixed to every MultiRound hand commitment
+pub const HAND_COMMITMENT_DOMAIN: &[u8] = b"solduel:hand:v1";
+
+/// Domain tags for Hold'em deck leaves, their salts and the deck tree nodes
+pub const CARD_LEAF_DOMAIN: &[u8] = b"solduel:card:v2";
+pub const CARD_SALT_DOMAIN: &[u8] = b"solduel:salt:v1";
+pub const DECK_NODE_DOMAIN: &[u8] = b"solduel:deck:v2";
+
+/// Domain tag prefixed to every private game access code hash
+pub const ACCESS_CODE_DOMAIN: &[u8] = b"solduel:access:v1";
+
+/// Compute the canonical commitment for a duel move.
+///
+/// The hash covers the program id, game id, round and player as well as the
+/// move and nonce, so a commitment copied from another player, game or round
+/// can never be revealed. Clients should use this to build `commit_move` input.
+pub fn compute_move_commitment(
+    program_id: &Pubkey,
+    game_id: u64,
+    round: u8,
+    player: &Pubkey,
+    game_move: GameMove,
+    nonce: &[u8; 32],
+) -> Result<[u8; 32]> {
+    let move_bytes = game_move.try_to_vec()?;
+    
+    Ok(hashv(&[
+        MOVE_COMMITMENT_DOMAIN,
+        program_id.as_ref(),
+        &game_id.to_le_bytes(),
+        &[round],
+        player.as_ref(),
+        &move_bytes,
+        nonce,
+    ]).to_bytes())
+}
+
+/// Compute the commitment a MultiRound player submits when taking a seat.
+///
+/// The secret is revealed at showdown and, combined with the other players'
+/// secrets, deals every hand, so nobody can steer the cards alone.
+pub fn compute_hand_commitment(
+    program_id: &Pubkey,
+    game_id: u64,
+    player: &Pubkey,
+    secret: &[u8; 32],
+) -> [u8; 32] {
+    hashv(&[
+        HAND_COMMITMENT_DOMAIN,
+        program_id.as_ref(),
+        &game_id.to_le_bytes(),
+        player.as_ref(),
+        secret,
+    ]).to_bytes()
+}
+
+/// Derive the salt hiding one entry of a Hold'em deck permutation.
+///
+/// Entries are opened one at a time with their own salt, so opening one
+/// never exposes another; the seed is only revealed at showdown.
+pub fn compute_card_salt(salt_seed: &[u8; 32], index: u8) -> [u8; 32] {
+    hashv(&[CARD_SALT_DOMAIN, salt_seed, &[index]]).to_bytes()
+}
+
+/// Compute the leaf committing a Hold'em seat to `permutation[index] == value`.
+pub fn compute_card_leaf(
+    program_id: &Pubkey,
+    game_id: u64,
+    player: &Pubkey,
+    index: u8,
+    value: u8,
+    salt: &[u8; 32],
+) -> [u8; 32] {
+    hashv(&[
+        CARD_LEAF_DOMAIN,
+        program_id.as_ref(),
+        &game_id.to_le_bytes(),
+        player.as_ref(),
+        &[index, value],
+        salt,
+    ]).to_bytes()
+}
+
+/// Compute the Merkle root over a seat's secret deck permutation.
+///
+/// This is the hand commitment submitted when taking a Hold'em seat. The
+/// tree has one leaf per deck entry, padded with empty leaves to a power of
+/// two, so each entry can later be opened on its own with a proof.
+pub fn compute_deck_commitment(
+    program_id: &Pubkey,
+    game_id: u64,
+    player: &Pubkey,
+    permutation: &[u8; DECK_CARDS],
+    salt_seed: &[u8; 32],
+) -> [u8; 32] {
+    let mut level: Vec<[u8; 32]> = (0..1usize << DECK_PROOF_DEPTH)
+        .map(|index| match permutation.get(index) {
+            Some(value) => {
+                let salt = compute_card_salt(salt_seed, index as u8);
+                compute_card_leaf(program_id, game_id, player, index as u8, *value, &salt)
+            }
+            None => [0; 32],
+        })
+        .collect();
+    
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| hashv(&[DECK_NODE_DOMAIN, &pair[0], &pair[1]]).to_bytes())
+            .collect();
+    }
+    level[0]
+}
+
+/// Check that `leaf` sits at `index` in the deck tree with the given root.
+pub fn verify_card_proof(
+    leaf: [u8; 32],
+    index: u8,
+    proof: &[[u8; 32]; DECK_PROOF_DEPTH],
+    root: &[u8; 32],
+) -> bool {
+    let node = proof.iter().enumerate().fold(leaf, |node, (depth, sibling)| {
+        if (index >> depth) & 1 == 0 {
+            hashv(&[DECK_NODE_DOMAIN, &node, sibling]).to_bytes()
+        } else {
+            hashv(&[DECK_NODE_DOMAIN, sibling, &node]).to_bytes()
+        }
+    });
+    node == *root
+}
+
+/// Compute the hash a creator stores to gate a private game behind an access code.
+///
+/// The hash is bound to the game, so a code shared for one game does not open
+/// any other.
+pub fn compute_access_code_hash(program_id: &Pubkey, game_id: u64, code: &[u8; 32]) -> [u8; 32] {
+    hashv(&[
+        ACCESS_CODE_DOMAIN,
+        program_id.as_ref(),
+        &game_id.to_le_bytes(),
+        code,
+    ]).to_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // Build every level of a deck tree, leaves first
+    fn deck_levels(
+        program_id: &Pubkey,
+        player: &Pubkey,
+        permutation: &[u8; DECK_CARDS],
+        salt_seed: &[u8; 32],
+    ) -> Vec<Vec<[u8; 32]>> {
+        let mut levels = vec![(0..1usize << DECK_PROOF_DEPTH)
+            .map(|index| match permutation.get(index) {
+                Some(value) => {
+                    let salt = compute_card_salt(salt_seed, index as u8);
+                    compute_card_leaf(program_id, 7, player, index as u8, *value, &salt)
+                }
+                None => [0; 32],
+            })
+            .collect::<Vec<_>>()];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| hashv(&[DECK_NODE_DOMAIN, &pair[0], &pair[1]]).to_bytes())
+                .collect();
+            levels.push(next);
+        }
+        levels
+    }
+    
+    #[test]
+    fn card_openings_verify_against_the_deck_commitment() {
+        let program_id = Pubkey::new_unique();
+        let player = Pubkey::new_unique();
+        let salt_seed = [9u8; 32];
+        let mut permutation = [0u8; DECK_CARDS];
+        for (i, value) in permutation.iter_mut().enumerate() {
+            *value = ((i * 7 + 3) % DECK_CARDS) as u8;
+        }
+        
+        let root = compute_deck_commitment(&program_id, 7, &player, &permutation, &salt_seed);
+        let levels = deck_levels(&program_id, &player, &permutation, &salt_seed);
+        assert_eq!(levels.last().unwrap()[0], root);
+        
+        for index in [0u8, 5, 31, 51] {
+            let mut proof = [[0u8; 32]; DECK_PROOF_DEPTH];
+            for (depth, sibling) in proof.iter_mut().enumerate() {
+                *sibling = levels[depth][((index as usize) >> depth) ^ 1];
+            }
+            let salt = compute_card_salt(&salt_seed, index);
+            let value = permutation[index as usize];
+            let leaf = compute_card_leaf(&program_id, 7, &player, index, value, &salt);
+            assert!(verify_card_proof(leaf, index, &proof, &root));
+            
+            // The same proof cannot open a different value or position
+            let forged = compute_card_leaf(&program_id, 7, &player, index, (value + 1) % DECK_CARDS as u8, &salt);
+            assert!(!verify_card_proof(forged, index, &proof, &root));
+            assert!(!verify_card_proof(leaf, index ^ 1, &proof, &root));
+        }
+    }
+}
@@ -11,15 +11,66 @@ pub const MAX_PLAYERS: usize = 100;
 pub const MAX_ACTIONS: usize = 50;
 pub const MAX_ITEMS: usize = 10;
 pub const MAX_ROUNDS: u8 = 10;
+pub const MAX_BEST_OF: u8 = 7;
+
+/// Dice Battle limits
+pub const MAX_DICE: u8 = 5;
+pub const MIN_DICE_SIDES: u8 = 2;
+pub const MAX_DICE_SIDES: u8 = 20;
+pub const DICE_MAX_REROLLS: u8 = 8;
+
+/// Largest `GameMove::Number` that packs below the lizard/Spock bytes
+pub const MAX_MOVE_NUMBER: u8 = 247;
+
+/// Battle royale limits
+pub const MIN_BATTLE_ROYALE_PLAYERS: u8 = 3;
+pub const MAX_BATTLE_ROYALE_PLAYERS: u8 = 16;
+
+/// Cards in a standard deck, and the depth of the Merkle tree a Hold'em seat
+/// commits its deck permutation to (64 leaves cover the 52 cards)
+pub const DECK_CARDS: usize = 52;
+pub const DECK_PROOF_DEPTH: usize = 6;
+
+/// Heads-up Hold'em deck layout: each seat's two hole cards, then the
+/// flop, turn and river, one deck position per card
+pub const HOLDEM_SEATS: usize = 2;
+pub const HOLDEM_HOLE_CARDS: usize = 2;
+pub const HOLDEM_BOARD_CARDS: usize = 5;
+pub const HOLDEM_DECK_SLOTS: usize = HOLDEM_SEATS * HOLDEM_HOLE_CARDS + HOLDEM_BOARD_CARDS;
+pub const HOLDEM_BETTING_ROUNDS: u8 = 4; // Preflop, flop, turn, river
+
+/// Seats at a MultiRound ring table
+pub const MIN_TABLE_SEATS: u8 = 2;
+pub const MAX_TABLE_SEATS: usize = 9;
+
+/// Fixed-limit betting: the opening bet plus three raises per round
+pub const FIXED_LIMIT_MAX_RAISES: u8 = 4;
 
 /// Default values
 pub const DEFAULT_MIN_STAKE: u64 = 100_000_000; // 0.1 SOL
 pub const DEFAULT_MAX_STAKE: u64 = 10_000_000_000; // 10 SOL
 pub const DEFAULT_TIMEOUT: u64 = 3600; // 1 hour
-pub const DEFAULT_PLATFORM_FEE: u8 = 2; // 2%
+pub const DEFAULT_PLATFORM_FEE_BPS: u16 = 200; // 2%
 pub const DEFAULT_FOLD_PENALTY: u8 = 10; // 10%
+pub const DEFAULT_CANCEL_FEE_BPS: u16 = 0; // Agreed cancellations are free
+
+/// Layout version written by this program; see `ConfigurationAccount::migrate`
+pub const CONFIG_VERSION: u8 = 1;
+
+/// Platform fees are charged in basis points of the amount paid out
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MAX_PLATFORM_FEE_BPS: u16 = 1_000; // 10%
+
+/// Commit-reveal phase deadlines for duels
+pub const COMMIT_PHASE_TIMEOUT: i64 = 300; // 5 minutes
+pub const REVEAL_PHASE_TIMEOUT: i64 = 300; // 5 minutes
+
+/// Per-action clock bounds for MultiRound betting, in seconds
+pub const MIN_ACTION_TIMEOUT: u8 = 30;
+pub const MAX_ACTION_TIMEOUT: u8 = 120;
 
 /// Lottery parameters
 pub const LOTTERY_TICKET_PRICE: u64 = 50_000_000; // 0.05 SOL
 pub const LOTTERY_MAX_TICKETS_PER_PLAYER: u32 = 100;
-pub const LOTTERY_DRAW_INTERVAL: i64 = 86400; // 24 hours
\ No newline at end of file
+pub const LOTTERY_DRAW_INTERVAL: i64 = 86400; // 24 hours
+pub const MIN_LOTTERY_ENTRIES: u8 = 2;
\ No newline at end of file
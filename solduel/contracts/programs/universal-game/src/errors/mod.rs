@@ -64,6 +64,69 @@ pub enum GameError {
     
     #[msg("Action not allowed in current round")]
     ActionNotAllowedInRound,
+    
+    #[msg("Game variant not supported for this game type")]
+    InvalidVariant,
+    
+    #[msg("Commitment is empty or already used in this game")]
+    DuplicateCommitment,
+    
+    #[msg("Player has been eliminated")]
+    PlayerEliminated,
+    
+    #[msg("It is not this player's turn to act")]
+    NotPlayerTurn,
+    
+    #[msg("Cannot check while facing a bet")]
+    CannotCheckFacingBet,
+    
+    #[msg("No outstanding bet to call")]
+    NothingToCall,
+    
+    #[msg("Raise is smaller than the minimum raise")]
+    RaiseTooSmall,
+    
+    #[msg("Hand commitment is required for MultiRound games only")]
+    HandCommitmentRequired,
+    
+    #[msg("Cards for this betting round have not been dealt")]
+    CardsNotDealt,
+    
+    #[msg("Raise must be exactly one bet unit in fixed-limit games")]
+    InvalidFixedLimitRaise,
+    
+    #[msg("No more raises allowed in this betting round")]
+    RaiseCapReached,
+    
+    #[msg("Raise exceeds the pot limit")]
+    RaiseExceedsPotLimit,
+    
+    #[msg("Player is all-in and cannot act")]
+    PlayerAllIn,
+    
+    #[msg("Player has folded")]
+    PlayerFolded,
+    
+    #[msg("The deadline has not passed yet")]
+    DeadlineNotReached,
+    
+    #[msg("Game is invite-only")]
+    NotInvited,
+    
+    #[msg("Access code is missing or wrong")]
+    InvalidAccessCode,
+    
+    #[msg("No cancellation has been offered")]
+    NoCancelOffer,
+    
+    #[msg("The first seat has not opened its deck for these cards")]
+    DeckNotOpened,
+    
+    #[msg("Revealed deck is not a permutation of the cards")]
+    InvalidDeck,
+    
+    #[msg("A rematch of this game has already been proposed")]
+    AlreadyRematched,
 }
 
 #[error_code]
@@ -88,6 +151,15 @@ pub enum TreasuryError {
     
     #[msg("Game is not cancelled")]
     GameNotCancelled,
+    
+    #[msg("Winnings have already been claimed")]
+    WinningsAlreadyClaimed,
+    
+    #[msg("Treasury account does not match the configured treasury")]
+    InvalidTreasury,
+    
+    #[msg("No penalty bond to return")]
+    NoBondToReturn,
 }
 
 #[error_code]
@@ -1,14 +1,23 @@
 use anchor_lang::prelude::*;
-use crate::state::{GameType, GameState, GameMove, BetAction};
+use crate::state::{
+    GameType, GameState, GameMove, GameVariant, DrawPolicy, BettingStructure, BetAction,
+    FoldPenaltyRecipient,
+};
 
 /// Event emitted when a new game is created
 #[event]
 pub struct GameCreated {
     pub game_id: u64,
     pub game_type: GameType,
+    pub variant: Option<GameVariant>,
     pub creator: Pubkey,
     pub stake_amount: u64,
+    pub penalty_bond: u64,
     pub max_players: u8,
+    pub max_rounds: u8,
+    pub draw_policy: Option<DrawPolicy>,
+    pub betting_structure: Option<BettingStructure>,
+    pub is_private: bool,
     pub timestamp: i64,
 }
 
@@ -51,6 +60,106 @@ pub struct MoveRevealed {
     pub timestamp: i64,
 }
 
+/// Event emitted when every duelist has revealed and the round is scored
+#[event]
+pub struct RoundResolved {
+    pub game_id: u64,
+    pub round: u8,
+    pub winner: Option<Pubkey>,
+    pub series_wins: [u8; 2],
+    pub timestamp: i64,
+}
+
+/// Event emitted when a duel ends level and is settled by refunds or a split
+#[event]
+pub struct GameDrawn {
+    pub game_id: u64,
+    pub policy: DrawPolicy,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a player reclaims their stake from a drawn duel
+#[event]
+pub struct DrawRefunded {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a player's stake is returned from a cancelled game
+#[event]
+pub struct StakeRefunded {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub amount: u64,
+    pub penalty_amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a player takes back a penalty bond they did not forfeit
+#[event]
+pub struct BondReturned {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a player claims their share of a split pot
+#[event]
+pub struct PotSplit {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a drawn duel reopens for a sudden-death round
+#[event]
+pub struct SuddenDeathStarted {
+    pub game_id: u64,
+    pub round: u8,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a battle-royale player is knocked out
+#[event]
+pub struct PlayerEliminated {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub round: u8,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a battle-royale round knocks nobody out and is replayed
+#[event]
+pub struct RoundReplayed {
+    pub game_id: u64,
+    pub round: u8,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a coin-flip duel is settled
+#[event]
+pub struct CoinFlipped {
+    pub game_id: u64,
+    pub outcome: GameMove,
+    pub winner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted for each player's final roll in a Dice Battle
+#[event]
+pub struct DiceRolled {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub dice: Vec<u8>,
+    pub total: u16,
+    pub rerolls: u8,
+    pub timestamp: i64,
+}
+
 /// Event emitted when a bet action is placed
 #[event]
 pub struct BetPlaced {
@@ -62,6 +171,16 @@ pub struct BetPlaced {
     pub timestamp: i64,
 }
 
+/// Event emitted when a main or side pot is awarded at settlement
+#[event]
+pub struct PotAwarded {
+    pub game_id: u64,
+    pub pot_index: u8,
+    pub amount: u64,
+    pub winners: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
 /// Event emitted when lottery tickets are purchased
 #[event]
 pub struct LotteryEntered {
@@ -104,6 +223,22 @@ pub struct GameCancelled {
     pub timestamp: i64,
 }
 
+/// Event emitted when a player offers or agrees to cancel a game in play
+#[event]
+pub struct CancelOffered {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a player withdraws their offer to cancel
+#[event]
+pub struct CancelWithdrawn {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub timestamp: i64,
+}
+
 /// Event emitted when winnings are claimed
 #[event]
 pub struct WinningsClaimed {
@@ -140,6 +275,44 @@ pub struct VrfFulfilled {
     pub timestamp: i64,
 }
 
+/// Event emitted when a MultiRound player reveals their secret at showdown
+#[event]
+pub struct HandRevealed {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a MultiRound showdown deals and scores the hands
+#[event]
+pub struct ShowdownResolved {
+    pub game_id: u64,
+    pub hands: Vec<[u8; 5]>,
+    pub hand_categories: Vec<u8>,
+    pub winner: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+/// Event emitted when Hold'em community cards are dealt
+#[event]
+pub struct BoardDealt {
+    pub game_id: u64,
+    pub round: u8,
+    pub cards: Vec<u8>,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a Hold'em showdown is scored
+#[event]
+pub struct HoldemShowdown {
+    pub game_id: u64,
+    pub hole_cards: [[u8; 2]; 2],
+    pub board: [u8; 5],
+    pub hand_categories: [u8; 2],
+    pub winner: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
 /// Event emitted when a player is penalized for timeout
 #[event]
 pub struct PlayerTimedOut {
@@ -149,6 +322,46 @@ pub struct PlayerTimedOut {
     pub timestamp: i64,
 }
 
+/// Event emitted when a player's action clock runs out and the game acts for them
+#[event]
+pub struct ActionTimedOut {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub action: BetAction,
+    pub round: u8,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a player folds and forfeits their penalty bond
+#[event]
+pub struct PlayerFolded {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub penalty_amount: u64,
+    pub recipient: FoldPenaltyRecipient,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a player proposes a rematch of a completed game
+#[event]
+pub struct RematchProposed {
+    pub previous_game_id: u64,
+    pub game_id: u64,
+    pub proposer: Pubkey,
+    pub rolled_in: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a player takes their seat in a rematch
+#[event]
+pub struct RematchAccepted {
+    pub previous_game_id: u64,
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub rolled_in: u64,
+    pub timestamp: i64,
+}
+
 /// Reason for game cancellation
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 pub enum CancelReason {
@@ -156,4 +369,5 @@ pub enum CancelReason {
     CreatorCancelled,
     Timeout,
     AdminIntervention,
+    MutualAgreement,
 }
\ No newline at end of file
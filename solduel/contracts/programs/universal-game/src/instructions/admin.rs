@@ -13,22 +13,37 @@ pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
     config.max_rounds = MAX_ROUNDS;
     config.fold_penalty = DEFAULT_FOLD_PENALTY;
     config.randomness_method = 0; // commit-reveal by default
-    config.platform_fee = DEFAULT_PLATFORM_FEE;
+    config.platform_fee = 0; // Legacy percentage, replaced by the basis-point fees
     config.item_prices = vec![100_000_000; MAX_ITEMS]; // 0.1 SOL per item
     config.timeout = DEFAULT_TIMEOUT;
     config.ticket_conversion = 1; // 1 ticket per unit
     config.game_counter = 0;
-    config.reserved = [0; 128];
+    config.fold_penalty_recipient = FoldPenaltyRecipient::Treasury;
+    config.cancel_fee = 0; // Legacy percentage, replaced by cancel_fee_bps
+    config.duel_fee_bps = DEFAULT_PLATFORM_FEE_BPS;
+    config.multi_round_fee_bps = DEFAULT_PLATFORM_FEE_BPS;
+    config.lottery_fee_bps = DEFAULT_PLATFORM_FEE_BPS;
+    config.max_rake = 0; // No cap
+    config.config_version = CONFIG_VERSION;
+    config.cancel_fee_bps = DEFAULT_CANCEL_FEE_BPS;
+    config.reserved = [0; 109];
     
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_config(
     ctx: Context<UpdateConfig>,
     min_stake: Option<u64>,
     max_stake: Option<u64>,
-    platform_fee: Option<u8>,
+    duel_fee_bps: Option<u16>,
+    multi_round_fee_bps: Option<u16>,
+    lottery_fee_bps: Option<u16>,
+    max_rake: Option<u64>,
     timeout: Option<u64>,
+    fold_penalty: Option<u8>,
+    fold_penalty_recipient: Option<FoldPenaltyRecipient>,
+    cancel_fee_bps: Option<u16>,
 ) -> Result<()> {
     let config = &mut ctx.accounts.config;
     
@@ -39,6 +54,9 @@ pub fn update_config(
         GameError::UnauthorizedPlayer
     );
     
+    // Carry older fields over first so the updates below are not overwritten
+    config.migrate();
+    
     if let Some(min) = min_stake {
         config.min_stake = min;
     }
@@ -48,9 +66,23 @@ pub fn update_config(
         config.max_stake = max;
     }
     
-    if let Some(fee) = platform_fee {
-        require!(fee <= 10, GameError::InvalidConfig); // Max 10% fee
-        config.platform_fee = fee;
+    if let Some(fee) = duel_fee_bps {
+        require!(fee <= MAX_PLATFORM_FEE_BPS, GameError::InvalidConfig); // Max 10% fee
+        config.duel_fee_bps = fee;
+    }
+    
+    if let Some(fee) = multi_round_fee_bps {
+        require!(fee <= MAX_PLATFORM_FEE_BPS, GameError::InvalidConfig);
+        config.multi_round_fee_bps = fee;
+    }
+    
+    if let Some(fee) = lottery_fee_bps {
+        require!(fee <= MAX_PLATFORM_FEE_BPS, GameError::InvalidConfig);
+        config.lottery_fee_bps = fee;
+    }
+    
+    if let Some(cap) = max_rake {
+        config.max_rake = cap; // 0 lifts the cap
     }
     
     if let Some(t) = timeout {
@@ -58,6 +90,34 @@ pub fn update_config(
         config.timeout = t;
     }
     
+    if let Some(penalty) = fold_penalty {
+        require!(penalty <= 100, GameError::InvalidConfig);
+        config.fold_penalty = penalty;
+    }
+    
+    if let Some(recipient) = fold_penalty_recipient {
+        config.fold_penalty_recipient = recipient;
+    }
+    
+    if let Some(fee) = cancel_fee_bps {
+        require!(fee <= MAX_PLATFORM_FEE_BPS, GameError::InvalidConfig); // Same cap as the platform fee
+        config.cancel_fee_bps = fee;
+    }
+    
+    Ok(())
+}
+
+/// Upgrade a config written by an earlier program version (admin only)
+pub fn migrate_config(ctx: Context<UpdateConfig>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    
+    require_keys_eq!(
+        ctx.accounts.admin.key(),
+        config.admin,
+        GameError::UnauthorizedPlayer
+    );
+    require!(config.migrate(), GameError::InvalidConfig);
+    
     Ok(())
 }
 
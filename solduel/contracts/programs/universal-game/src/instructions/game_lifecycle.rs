@@ -1,15 +1,35 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use crate::state::{ConfigurationAccount, GameAccountOptimized, GameType, GameState};
+use crate::state::{
+    ConfigurationAccount, GameAccountOptimized, GameType, GameState, GameMove, GameVariant,
+    DrawPolicy, BettingStructure, GameAccess, FLAG_MUTUAL_CANCEL,
+};
+use crate::commitment::compute_access_code_hash;
 use crate::constants::*;
 use crate::errors::GameError;
 use crate::events::*;
+use crate::instructions::simple_duel::settle_phase_timeout;
+use crate::instructions::holdem::{awaiting_cards, settle_card_timeout};
+use crate::instructions::multi_round::{
+    open_betting_round, settle_action_timeout, settle_showdown_timeout,
+};
+use crate::instructions::treasury::{
+    calculate_platform_fee, penalty_bond, rake_taken, vault_rent_reserve,
+};
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_game(
     ctx: Context<CreateGame>,
     game_type: GameType,
     stake_amount: u64,
     max_players: Option<u8>,
+    variant: Option<GameVariant>,
+    best_of: Option<u8>,
+    draw_policy: Option<DrawPolicy>,
+    hand_commitment: Option<[u8; 32]>,
+    betting_structure: Option<BettingStructure>,
+    action_timeout: Option<u8>,
+    access: Option<GameAccess>,
 ) -> Result<()> {
     let config = &ctx.accounts.config;
     let mut game = ctx.accounts.game.load_init()?;
@@ -21,6 +41,45 @@ pub fn create_game(
         GameError::InvalidConfig
     );
     
+    // Validate variant against game type
+    let game_variant = match (game_type, variant) {
+        (GameType::SimpleDuel, None) => GameVariant::RockPaperScissors,
+        (GameType::SimpleDuel, Some(GameVariant::CoinFlip { creator_call })) => {
+            require!(
+                creator_call == GameMove::Heads || creator_call == GameMove::Tails,
+                GameError::InvalidMove
+            );
+            GameVariant::CoinFlip { creator_call }
+        }
+        (GameType::SimpleDuel, Some(GameVariant::DiceBattle { dice_count, sides, tie_break })) => {
+            require!(
+                (1..=MAX_DICE).contains(&dice_count),
+                GameError::InvalidConfig
+            );
+            require!(
+                (MIN_DICE_SIDES..=MAX_DICE_SIDES).contains(&sides),
+                GameError::InvalidConfig
+            );
+            GameVariant::DiceBattle { dice_count, sides, tie_break }
+        }
+        (GameType::SimpleDuel, Some(GameVariant::BattleRoyale { players })) => {
+            require!(
+                (MIN_BATTLE_ROYALE_PLAYERS..=MAX_BATTLE_ROYALE_PLAYERS).contains(&players),
+                GameError::InvalidConfig
+            );
+            GameVariant::BattleRoyale { players }
+        }
+        // Hold'em hole cards still change hands off-chain with nothing to
+        // enforce the exchange, so no Hold'em tables are opened for now
+        (_, Some(GameVariant::Holdem)) => return Err(GameError::InvalidVariant.into()),
+        (GameType::SimpleDuel, Some(v)) => v,
+        (_, None) => GameVariant::RockPaperScissors,
+        (_, Some(_)) => return Err(GameError::InvalidVariant.into()),
+    };
+    
+    // MultiRound players commit to their showdown secret when taking a seat
+    let hand_commitment = checked_hand_commitment(game_type, hand_commitment)?;
+    
     // Initialize game with optimized structure
     game.game_id = config.game_counter;
     game.set_type_and_state(game_type, GameState::Waiting);
@@ -29,14 +88,84 @@ pub fn create_game(
     // Initialize player arrays
     game.players[0] = ctx.accounts.player.key();
     game.stakes[0] = stake_amount;
+    game.commit_hashes[0] = hand_commitment;
     game.player_count = 1;
     game.pot_total = stake_amount;
     
-    // Set rounds
-    let max_rounds = match game_type {
-        GameType::SimpleDuel => 1,
-        GameType::MultiRound => config.max_rounds,
-        GameType::Lottery => 1,
+    // Series length only applies to duels and must be odd so someone clinches
+    let best_of = match (game_type, best_of) {
+        (_, None) => 1,
+        (GameType::SimpleDuel, Some(n)) => {
+            require!(
+                n % 2 == 1 && n <= MAX_BEST_OF,
+                GameError::InvalidConfig
+            );
+            n
+        }
+        (_, Some(_)) => return Err(GameError::InvalidVariant.into()),
+    };
+    
+    // Draw policy only applies to duels; refunds are the safe default
+    let draw_policy = match (game_type, draw_policy) {
+        (GameType::SimpleDuel, policy) => policy.unwrap_or(DrawPolicy::Refund),
+        (_, None) => DrawPolicy::Refund,
+        (_, Some(_)) => return Err(GameError::InvalidVariant.into()),
+    };
+    
+    // Betting structure only applies to MultiRound; no-limit is the default
+    let betting_structure = match (game_type, betting_structure) {
+        (GameType::MultiRound, structure) => structure.unwrap_or(BettingStructure::NoLimit),
+        (_, None) => BettingStructure::NoLimit,
+        (_, Some(_)) => return Err(GameError::InvalidConfig.into()),
+    };
+    
+    // Only MultiRound betting runs on a per-action clock
+    let action_timeout = match (game_type, action_timeout) {
+        (GameType::MultiRound, Some(seconds)) => {
+            require!(
+                (MIN_ACTION_TIMEOUT..=MAX_ACTION_TIMEOUT).contains(&seconds),
+                GameError::InvalidConfig
+            );
+            seconds
+        }
+        (_, None) => 0,
+        (_, Some(_)) => return Err(GameError::InvalidConfig.into()),
+    };
+    
+    let seat_cap = checked_seat_cap(game_type, game_variant, max_players)?;
+    
+    // Lotteries stay public; a direct challenge is for a single opponent
+    let access = access.unwrap_or(GameAccess::Public);
+    match access {
+        GameAccess::Public => {}
+        GameAccess::Invite { player } => {
+            require!(
+                game_type != GameType::Lottery && seat_cap == 2,
+                GameError::InvalidConfig
+            );
+            require!(
+                player != ctx.accounts.player.key() && player != Pubkey::default(),
+                GameError::InvalidConfig
+            );
+        }
+        GameAccess::AccessCode { code_hash } => {
+            require!(
+                game_type != GameType::Lottery && code_hash != [0; 32],
+                GameError::InvalidConfig
+            );
+        }
+    }
+    
+    // Set rounds; a battle royale knocks out at least one player per round
+    let max_rounds = match (game_type, game_variant) {
+        (GameType::SimpleDuel, GameVariant::BattleRoyale { players }) => {
+            require!(best_of == 1, GameError::InvalidConfig);
+            players - 1
+        }
+        (GameType::SimpleDuel, _) => best_of,
+        (GameType::MultiRound, GameVariant::Holdem) => HOLDEM_BETTING_ROUNDS,
+        (GameType::MultiRound, _) => config.max_rounds,
+        (GameType::Lottery, _) => 1,
     };
     game.set_rounds(0, max_rounds);
     
@@ -50,8 +179,33 @@ pub fn create_game(
     game.platform_fee_collected = 0;
     game.treasury = config.treasury;
     game.flags = 0;
-    
-    // Transfer stake to vault
+    game.set_variant(game_variant);
+    game.series_wins = [0; 2];
+    game.set_draw_policy(draw_policy);
+    game.phase_deadline = 0;
+    game.penalty_amount = 0;
+    game.eliminated_mask = 0;
+    game.turn_index = 0;
+    game.acted_mask = 0;
+    game.min_raise = stake_amount;
+    game.set_betting_structure(betting_structure);
+    game.round_raises = 0;
+    game.all_in_mask = 0;
+    game.payouts = [0; MAX_TABLE_SEATS];
+    game.max_players = seat_cap;
+    game.dealer_index = 0;
+    game.folded_mask = 0;
+    game.action_timeout = action_timeout;
+    game.set_access(access);
+    game.rematch_of = Pubkey::default();
+    game.cancel_offers = 0;
+    game.claimed_mask = 0;
+    game.vault_bump = ctx.bumps.vault;
+    game.penalty_bond = penalty_bond(config, game_type, stake_amount)?;
+    game.bond_mask = 0;
+    
+    // Transfer stake and bond to vault, topping it up to its rent reserve
+    let reserve = vault_rent_reserve(&ctx.accounts.vault)?;
     system_program::transfer(
         CpiContext::new(
             ctx.accounts.system_program.to_account_info(),
@@ -60,36 +214,91 @@ pub fn create_game(
                 to: ctx.accounts.vault.to_account_info(),
             },
         ),
-        stake_amount,
+        stake_amount + game.penalty_bond + reserve,
     )?;
     
     // Update game counter
     let config = &mut ctx.accounts.config;
     config.game_counter += 1;
     
-    // Get default max players for game type
-    let max_default = match game_type {
-        GameType::SimpleDuel | GameType::MultiRound => 2,
-        GameType::Lottery => MAX_PLAYERS as u8,
-    };
-    
     // Emit event
     emit!(GameCreated {
         game_id: game.game_id,
         creator: ctx.accounts.player.key(),
         game_type,
+        variant: if game_type == GameType::Lottery { None } else { Some(game_variant) },
         stake_amount,
-        max_players: max_players.unwrap_or(max_default),
+        penalty_bond: game.penalty_bond,
+        max_players: seat_cap,
+        max_rounds,
+        draw_policy: if game_type == GameType::SimpleDuel { Some(draw_policy) } else { None },
+        betting_structure: if game_type == GameType::MultiRound {
+            Some(betting_structure)
+        } else {
+            None
+        },
+        is_private: access != GameAccess::Public,
         timestamp: clock.unix_timestamp,
     });
     
     Ok(())
 }
 
-pub fn join_game(ctx: Context<JoinGame>) -> Result<()> {
+pub fn join_game(
+    ctx: Context<JoinGame>,
+    hand_commitment: Option<[u8; 32]>,
+    access_code: Option<[u8; 32]>,
+) -> Result<()> {
     let mut game = ctx.accounts.game.load_mut()?;
     let clock = Clock::get()?;
+    let player_key = ctx.accounts.player.key();
+    
+    // Rematch seats are taken through accept_rematch
+    require!(
+        game.rematch_of == Pubkey::default(),
+        GameError::NotInvited
+    );
+    
+    // Private games only admit the invited player or holders of the code
+    match game.access() {
+        GameAccess::Public => {}
+        GameAccess::Invite { player } => {
+            require_keys_eq!(player_key, player, GameError::NotInvited);
+        }
+        GameAccess::AccessCode { code_hash } => {
+            let code = access_code.ok_or(GameError::InvalidAccessCode)?;
+            require!(
+                compute_access_code_hash(ctx.program_id, game.game_id, &code) == code_hash,
+                GameError::InvalidAccessCode
+            );
+        }
+    }
+    
+    seat_player(&mut game, player_key, hand_commitment, clock.unix_timestamp)?;
     
+    // Transfer stake and bond to vault
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.player.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+            },
+        ),
+        game.entry_fee + game.penalty_bond,
+    )?;
+    
+    Ok(())
+}
+
+/// Seat a player in a waiting game at the entry fee and start the game once
+/// every seat is taken. The caller collects the stake and penalty bond.
+pub(crate) fn seat_player(
+    game: &mut GameAccountOptimized,
+    player_key: Pubkey,
+    hand_commitment: Option<[u8; 32]>,
+    now: i64,
+) -> Result<()> {
     // Check game state
     require!(
         game.game_state() == GameState::Waiting,
@@ -97,59 +306,50 @@ pub fn join_game(ctx: Context<JoinGame>) -> Result<()> {
     );
     
     // Check if player already joined
-    let player_key = ctx.accounts.player.key();
-    let mut already_joined = false;
-    for i in 0..game.player_count as usize {
-        if game.players[i] == player_key {
-            already_joined = true;
-            break;
-        }
-    }
     require!(
-        !already_joined,
+        !game.players[..game.player_count as usize].contains(&player_key),
         GameError::PlayerAlreadyJoined
     );
     
     // Check max players
-    let max = match game.game_type() {
-        GameType::SimpleDuel | GameType::MultiRound => 2,
-        GameType::Lottery => MAX_PLAYERS as u8,
-    };
+    let max = game.max_players;
     require!(
         game.player_count < max,
         GameError::GameFull
     );
     
+    // Hand commitments must be unique within the game
+    let hand_commitment = checked_hand_commitment(game.game_type(), hand_commitment)?;
+    if hand_commitment != [0; 32] {
+        require!(
+            !game.commit_hashes[..game.player_count as usize].contains(&hand_commitment),
+            GameError::DuplicateCommitment
+        );
+    }
+    
     // Add player
     let entry_fee = game.entry_fee;
     let player_index = game.player_count as usize;
     game.players[player_index] = player_key;
     game.stakes[player_index] = entry_fee;
+    game.commit_hashes[player_index] = hand_commitment;
     game.player_count += 1;
     game.pot_total += entry_fee;
     
     // Update timestamp
     let start_time = game.start_time();
-    game.set_timestamps(start_time, clock.unix_timestamp as u32);
-    
-    // Transfer stake to vault
-    system_program::transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.player.to_account_info(),
-                to: ctx.accounts.vault.to_account_info(),
-            },
-        ),
-        game.entry_fee,
-    )?;
+    game.set_timestamps(start_time, now as u32);
     
     // Start game if ready
-    if game.game_type() != GameType::Lottery && game.player_count == 2 {
+    if game.game_type() != GameType::Lottery && game.player_count == max {
         let game_type = game.game_type();
         game.set_type_and_state(game_type, GameState::Active);
         let max_rounds = game.max_rounds();
         game.set_rounds(1, max_rounds);
+        game.phase_deadline = (now + COMMIT_PHASE_TIMEOUT) as u32;
+        if game_type == GameType::MultiRound {
+            open_betting_round(game);
+        }
     }
     
     // Emit event
@@ -158,7 +358,7 @@ pub fn join_game(ctx: Context<JoinGame>) -> Result<()> {
         player: player_key,
         stake_amount: entry_fee,
         player_count: game.player_count,
-        timestamp: clock.unix_timestamp,
+        timestamp: now,
     });
     
     Ok(())
@@ -193,43 +393,240 @@ pub fn cancel_game(ctx: Context<CancelGame>) -> Result<()> {
         timestamp: clock.unix_timestamp,
     });
     
-    // Refund will be handled separately
+    // Players reclaim their stakes through claim_refund or batch_refund
     
     Ok(())
 }
 
-pub fn force_finish(ctx: Context<ForceFinish>) -> Result<()> {
+pub fn offer_cancel(ctx: Context<MutualCancel>) -> Result<()> {
+    agree_to_cancel(ctx)
+}
+
+pub fn accept_cancel(ctx: Context<MutualCancel>) -> Result<()> {
+    require!(
+        ctx.accounts.game.load()?.cancel_offers != 0,
+        GameError::NoCancelOffer
+    );
+    agree_to_cancel(ctx)
+}
+
+pub fn withdraw_cancel(ctx: Context<MutualCancel>) -> Result<()> {
     let mut game = ctx.accounts.game.load_mut()?;
-    let config = &ctx.accounts.config;
     let clock = Clock::get()?;
     
-    // Check timeout
-    let last_action = game.last_action_time() as i64;
-    let elapsed = clock.unix_timestamp - last_action;
+    let player_key = ctx.accounts.player.key();
+    let player_index = cancelling_seat(&game, &player_key)?;
     require!(
-        elapsed > config.timeout as i64,
-        GameError::InvalidGameState
+        game.cancel_offers & (1 << player_index) != 0,
+        GameError::NoCancelOffer
     );
+    game.cancel_offers &= !(1 << player_index);
+    
+    let start_time = game.start_time();
+    game.set_timestamps(start_time, clock.unix_timestamp as u32);
     
-    // Determine winner based on last active player
-    // (Implementation depends on game type)
+    emit!(CancelWithdrawn {
+        game_id: game.game_id,
+        player: player_key,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+// Record a player's agreement to cancel. An offer stands until it is withdrawn
+// or the game ends; once every player still in has agreed, the game is
+// cancelled. The stakes of players already out are shared among those still
+// in, and each stake is returned through claim_refund, less the configured
+// cancel fee.
+fn agree_to_cancel(ctx: Context<MutualCancel>) -> Result<()> {
+    let mut game = ctx.accounts.game.load_mut()?;
+    let config = &ctx.accounts.config;
+    let clock = Clock::get()?;
+    
+    let player_key = ctx.accounts.player.key();
+    let player_index = cancelling_seat(&game, &player_key)?;
+    require!(
+        game.cancel_offers & (1 << player_index) == 0,
+        GameError::MoveAlreadySubmitted
+    );
+    game.cancel_offers |= 1 << player_index;
     
-    let game_type = game.game_type();
-    game.set_type_and_state(game_type, GameState::Completed);
     let start_time = game.start_time();
     game.set_timestamps(start_time, clock.unix_timestamp as u32);
     
-    // Emit event
-    emit!(PlayerTimedOut {
+    emit!(CancelOffered {
         game_id: game.game_id,
-        player: game.creator, // Placeholder - should determine actual player
-        penalty_amount: 0,
+        player: player_key,
         timestamp: clock.unix_timestamp,
     });
     
+    let agreed = (0..game.player_count as usize)
+        .all(|i| !still_in(&game, i) || game.cancel_offers & (1 << i) != 0);
+    if !agreed {
+        return Ok(());
+    }
+    
+    // Agreed cancellations are flagged so refunds carry the cancel fee
+    share_forfeited_stakes(&mut game);
+    game.set_flag(FLAG_MUTUAL_CANCEL, true);
+    let game_type = game.game_type();
+    game.set_type_and_state(game_type, GameState::Cancelled);
+    
+    let mut refund_amount = 0u64;
+    let mut fees_taken = rake_taken(&game);
+    for i in (0..game.player_count as usize).filter(|i| still_in(&game, *i)) {
+        let stake = game.stakes[i];
+        let fee = calculate_platform_fee(config, config.cancel_fee_bps(), stake, fees_taken);
+        fees_taken += fee;
+        refund_amount += stake - fee;
+    }
+    
+    emit!(GameCancelled {
+        game_id: game.game_id,
+        reason: crate::events::CancelReason::MutualAgreement,
+        refund_amount,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+pub fn force_finish(ctx: Context<ForceFinish>) -> Result<()> {
+    let mut game = ctx.accounts.game.load_mut()?;
+    let config = &ctx.accounts.config;
+    let clock = Clock::get()?;
+    
+    // Only games still in play can be forced; cancelled games keep their refunds
+    let state = game.game_state();
+    require!(
+        state == GameState::Active || state == GameState::Resolving,
+        GameError::InvalidGameState
+    );
+    
+    // Duels are governed by their per-phase deadlines
+    if game.game_type() == GameType::SimpleDuel {
+        settle_phase_timeout(&mut game, config.fold_penalty_recipient, clock.unix_timestamp)?;
+        let start_time = game.start_time();
+        game.set_timestamps(start_time, clock.unix_timestamp as u32);
+        return Ok(());
+    }
+    
+    // Everything else is a MultiRound table: a showdown past its reveal
+    // deadline, a Hold'em street stalled on dealing, or a seat whose action
+    // clock ran out, where tables without a clock use the configured timeout
+    require!(
+        game.game_type() == GameType::MultiRound,
+        GameError::InvalidGameType
+    );
+    if state == GameState::Resolving {
+        settle_showdown_timeout(&mut game, config.fold_penalty_recipient, clock.unix_timestamp)?;
+    } else if awaiting_cards(&game) {
+        settle_card_timeout(&mut game, config.fold_penalty_recipient, clock.unix_timestamp)?;
+    } else {
+        settle_action_timeout(&mut game, config, clock.unix_timestamp)?;
+    }
+    let start_time = game.start_time();
+    game.set_timestamps(start_time, clock.unix_timestamp as u32);
+    
     Ok(())
 }
 
+// Seat of a player still in a duel or table that is in play, who may offer,
+// accept or withdraw a cancellation
+fn cancelling_seat(game: &GameAccountOptimized, player: &Pubkey) -> Result<usize> {
+    let state = game.game_state();
+    require!(
+        state == GameState::Active || state == GameState::Resolving,
+        GameError::InvalidGameState
+    );
+    require!(
+        game.game_type() != GameType::Lottery,
+        GameError::InvalidGameType
+    );
+    
+    let player_index = game.players[..game.player_count as usize]
+        .iter()
+        .position(|p| p == player)
+        .ok_or(GameError::UnauthorizedPlayer)?;
+    require!(
+        still_in(game, player_index),
+        GameError::PlayerEliminated
+    );
+    Ok(player_index)
+}
+
+// Whether a seat has neither been knocked out nor folded
+fn still_in(game: &GameAccountOptimized, seat: usize) -> bool {
+    !game.is_eliminated(seat) && game.folded_mask & (1 << seat) == 0
+}
+
+/// Share the stakes of players who are out among the players still in.
+///
+/// Those players have already lost their stakes, so a cancellation or a
+/// refunded showdown pays them only to the others; any odd lamports go to
+/// the earliest player still in.
+pub(crate) fn share_forfeited_stakes(game: &mut GameAccountOptimized) {
+    let (staying, out): (Vec<usize>, Vec<usize>) = (0..game.player_count as usize)
+        .partition(|i| still_in(game, *i));
+    if staying.is_empty() {
+        return;
+    }
+    
+    let forfeited: u64 = out.iter().map(|i| game.stakes[*i]).sum();
+    for i in out.into_iter() {
+        game.stakes[i] = 0;
+    }
+    let share = forfeited / staying.len() as u64;
+    for i in staying.iter() {
+        game.stakes[*i] += share;
+    }
+    game.stakes[staying[0]] += forfeited - share * staying.len() as u64;
+}
+
+/// MultiRound seats need a non-empty hand commitment; other game types take none
+pub(crate) fn checked_hand_commitment(
+    game_type: GameType,
+    hand_commitment: Option<[u8; 32]>,
+) -> Result<[u8; 32]> {
+    match (game_type, hand_commitment) {
+        (GameType::MultiRound, Some(commitment)) => {
+            require!(commitment != [0; 32], GameError::DuplicateCommitment);
+            Ok(commitment)
+        }
+        (GameType::MultiRound, None) | (_, Some(_)) => {
+            Err(GameError::HandCommitmentRequired.into())
+        }
+        (_, None) => Ok([0; 32]),
+    }
+}
+
+// Seat cap for a new game, checked against the legal range for its type:
+// duels seat exactly their players, ring tables seat up to MAX_TABLE_SEATS
+// with Hold'em heads-up only, and a lottery caps its ticket entries
+fn checked_seat_cap(
+    game_type: GameType,
+    variant: GameVariant,
+    max_players: Option<u8>,
+) -> Result<u8> {
+    let (min, max, default) = match (game_type, variant) {
+        (GameType::SimpleDuel, GameVariant::BattleRoyale { players }) => (players, players, players),
+        (GameType::SimpleDuel, _) => (2, 2, 2),
+        (GameType::MultiRound, GameVariant::Holdem) => {
+            (HOLDEM_SEATS as u8, HOLDEM_SEATS as u8, HOLDEM_SEATS as u8)
+        }
+        (GameType::MultiRound, _) => (MIN_TABLE_SEATS, MAX_TABLE_SEATS as u8, MIN_TABLE_SEATS),
+        (GameType::Lottery, _) => (MIN_LOTTERY_ENTRIES, MAX_PLAYERS as u8, MAX_PLAYERS as u8),
+    };
+    
+    let seats = max_players.unwrap_or(default);
+    require!(
+        (min..=max).contains(&seats),
+        GameError::InvalidConfig
+    );
+    Ok(seats)
+}
+
 #[derive(Accounts)]
 pub struct CreateGame<'info> {
     #[account(
@@ -252,13 +649,10 @@ pub struct CreateGame<'info> {
     )]
     pub config: Account<'info, ConfigurationAccount>,
     
-    /// CHECK: Vault account for holding stakes
+    /// CHECK: Vault PDA holding this game's stakes
     #[account(
         mut,
-        seeds = [
-            VAULT_SEED,
-            &config.game_counter.to_le_bytes()
-        ],
+        seeds = [VAULT_SEED, game.key().as_ref()],
         bump
     )]
     pub vault: AccountInfo<'info>,
@@ -274,8 +668,12 @@ pub struct JoinGame<'info> {
     #[account(mut)]
     pub game: AccountLoader<'info, GameAccountOptimized>,
     
-    /// CHECK: Vault account for holding stakes
-    #[account(mut)]
+    /// CHECK: Vault PDA holding this game's stakes
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump = game.load()?.vault_bump
+    )]
     pub vault: AccountInfo<'info>,
     
     #[account(mut)]
@@ -292,6 +690,17 @@ pub struct CancelGame<'info> {
     pub player: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct MutualCancel<'info> {
+    #[account(mut)]
+    pub game: AccountLoader<'info, GameAccountOptimized>,
+    
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, ConfigurationAccount>,
+    
+    pub player: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct ForceFinish<'info> {
     #[account(mut)]
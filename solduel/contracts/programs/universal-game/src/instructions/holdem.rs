@@ -0,0 +1,340 @@
+// Heads-up Texas Hold'em on top of the MultiRound betting flow.
+//
+// Each seat commits to a secret permutation of the 52 cards when it sits
+// down, as the Merkle root of one salted leaf per entry. The deck is the two
+// permutations composed: the card at deck position `k` is `second[first[k]]`.
+// A composition of permutations is itself a permutation, so no card can be
+// dealt twice, and neither seat can see or choose a card without the other.
+//
+// Positions 0-1 are the first seat's hole cards, 2-3 the second seat's, and
+// 4-8 the flop, turn and river. Hole cards are exchanged off-chain: the first
+// seat opens entries 2 and 3 of its permutation to the second seat, and takes
+// its own cards from the second seat by oblivious transfer of the entries at
+// `first[0]` and `first[1]`, checking every opening against the roots.
+// Board cards are opened on-chain before each street, first seat first, and
+// both permutations are revealed in full at showdown.
+//
+// The hole-card exchange has no on-chain step, so a seat could withhold it
+// with no deadline or forfeit. Until the program can deliver hole cards
+// itself, create_game does not open Hold'em tables.
+
+use anchor_lang::prelude::*;
+use crate::commitment::{compute_card_leaf, compute_deck_commitment, verify_card_proof};
+use crate::constants::{
+    HOLDEM_SEATS, HOLDEM_HOLE_CARDS, HOLDEM_BOARD_CARDS, HOLDEM_DECK_SLOTS, DECK_CARDS,
+    REVEAL_PHASE_TIMEOUT,
+};
+use crate::poker::{best_hand, hand_category};
+use crate::state::{
+    GameAccountOptimized, GameType, GameState, GameVariant, CardOpening, FoldPenaltyRecipient,
+    FLAG_HAS_TIMEOUT,
+};
+use crate::errors::GameError;
+use crate::instructions::multi_round::settle_pots;
+use crate::instructions::treasury::forfeit_bond;
+use crate::events::{BoardDealt, HoldemShowdown, PlayerTimedOut};
+
+/// First deck slot after the hole cards
+const BOARD_SLOT: usize = HOLDEM_SEATS * HOLDEM_HOLE_CARDS;
+
+pub fn reveal_board_cards(ctx: Context<RevealBoardCards>, openings: Vec<CardOpening>) -> Result<()> {
+    let mut game = ctx.accounts.game.load_mut()?;
+    let clock = Clock::get()?;
+    
+    require_holdem(&game)?;
+    require!(
+        game.game_state() == GameState::Active,
+        GameError::InvalidGameState
+    );
+    
+    let player_key = ctx.accounts.player.key();
+    let seat = seat_of(&game, &player_key)?;
+    
+    // Openings go in slot order for the street being dealt
+    let slots = pending_board_slots(&game).ok_or(GameError::InvalidGameState)?;
+    require!(openings.len() == slots.len(), GameError::InvalidReveal);
+    let slot_bits = slot_mask(&slots);
+    let first_opened = game.opened_mask & slot_bits == slot_bits;
+    
+    let mut values = Vec::with_capacity(slots.len());
+    for (slot, opening) in slots.iter().copied().zip(openings.iter()) {
+        // The first seat opens the deck position, the second seat opens the
+        // entry of its own permutation that position points at
+        let index = match (seat, first_opened) {
+            (0, false) => slot as u8,
+            (1, true) => game.deck_openings[slot],
+            (0, true) => return Err(GameError::MoveAlreadySubmitted.into()),
+            _ => return Err(GameError::DeckNotOpened.into()),
+        };
+        require!(
+            (opening.value as usize) < DECK_CARDS,
+            GameError::InvalidReveal
+        );
+    
+        let leaf = compute_card_leaf(
+            ctx.program_id,
+            game.game_id,
+            &player_key,
+            index,
+            opening.value,
+            &opening.salt,
+        );
+        require!(
+            verify_card_proof(leaf, index, &opening.proof, &game.commit_hashes[seat]),
+            GameError::InvalidReveal
+        );
+        values.push(opening.value);
+    }
+    
+    // A permutation never repeats an entry, so a repeat can only come from a
+    // seat that committed to something else; it stalls and forfeits
+    let earlier: Vec<u8> = if seat == 0 {
+        (BOARD_SLOT..HOLDEM_DECK_SLOTS)
+            .filter(|slot| game.opened_mask & (1 << slot) != 0)
+            .map(|slot| game.deck_openings[slot])
+            .collect()
+    } else {
+        game.board[..game.board_count as usize].to_vec()
+    };
+    for (i, value) in values.iter().enumerate() {
+        require!(
+            !earlier.contains(value) && !values[..i].contains(value),
+            GameError::InvalidReveal
+        );
+    }
+    
+    if seat == 0 {
+        for (slot, value) in slots.iter().copied().zip(values.iter()) {
+            game.deck_openings[slot] = *value;
+        }
+        game.opened_mask |= slot_bits;
+    
+        // The second seat gets a full window for its part
+        game.phase_deadline = (clock.unix_timestamp + REVEAL_PHASE_TIMEOUT) as u32;
+    } else {
+        deal_board(&mut game, &values, clock.unix_timestamp);
+    }
+    
+    let start_time = game.start_time();
+    game.set_timestamps(start_time, clock.unix_timestamp as u32);
+    
+    Ok(())
+}
+
+pub fn reveal_deck(
+    ctx: Context<RevealDeck>,
+    permutation: [u8; DECK_CARDS],
+    salt_seed: [u8; 32],
+) -> Result<()> {
+    let mut game = ctx.accounts.game.load_mut()?;
+    let clock = Clock::get()?;
+    
+    require_holdem(&game)?;
+    require!(
+        game.game_state() == GameState::Resolving,
+        GameError::InvalidGameState
+    );
+    
+    let player_key = ctx.accounts.player.key();
+    let seat = seat_of(&game, &player_key)?;
+    require!(
+        game.reveals_packed[seat] == 0,
+        GameError::MoveAlreadySubmitted
+    );
+    
+    // Every card must appear exactly once
+    let mut seen = 0u64;
+    for value in permutation.iter() {
+        require!(
+            (*value as usize) < DECK_CARDS && seen & (1 << value) == 0,
+            GameError::InvalidDeck
+        );
+        seen |= 1 << value;
+    }
+    
+    // The permutation must be the one committed when the seat was taken
+    let commitment = compute_deck_commitment(
+        ctx.program_id,
+        game.game_id,
+        &player_key,
+        &permutation,
+        &salt_seed,
+    );
+    require!(
+        commitment == game.commit_hashes[seat],
+        GameError::InvalidReveal
+    );
+    
+    game.deck_reveals[seat] = permutation;
+    game.reveals_packed[seat] = 1;
+    
+    let start_time = game.start_time();
+    game.set_timestamps(start_time, clock.unix_timestamp as u32);
+    
+    if game.reveals_packed[..HOLDEM_SEATS].iter().all(|revealed| *revealed != 0) {
+        resolve_holdem_showdown(&mut game, clock.unix_timestamp);
+    }
+    
+    Ok(())
+}
+
+/// Whether the board for the current betting round is on the table
+pub(crate) fn betting_open(game: &GameAccountOptimized) -> bool {
+    game.board_count as usize >= board_cards_for_round(game.current_round())
+}
+
+/// Whether an active table is stalled on dealing the next street
+pub(crate) fn awaiting_cards(game: &GameAccountOptimized) -> bool {
+    game.game_state() == GameState::Active && !betting_open(game)
+}
+
+/// Settle a table whose board openings are overdue.
+///
+/// The first seat opens first, so the seat that stalled is the first seat if
+/// it has not opened the street yet and the second seat otherwise. It forfeits
+/// its penalty bond, and the other seat wins the pot.
+pub(crate) fn settle_card_timeout(
+    game: &mut GameAccountOptimized,
+    recipient: FoldPenaltyRecipient,
+    now: i64,
+) -> Result<()> {
+    require!(awaiting_cards(game), GameError::InvalidGameState);
+    require!(
+        now > game.phase_deadline as i64,
+        GameError::DeadlineNotReached
+    );
+    
+    let slot_bits = pending_board_slots(game).map(|slots| slot_mask(&slots)).unwrap_or(0);
+    let stalled = if game.opened_mask & slot_bits == slot_bits { 1 } else { 0 };
+    let penalty = forfeit_bond(game, stalled, recipient)?;
+    
+    emit!(PlayerTimedOut {
+        game_id: game.game_id,
+        player: game.players[stalled],
+        penalty_amount: penalty,
+        timestamp: now,
+    });
+    game.set_flag(FLAG_HAS_TIMEOUT, true);
+    
+    settle_pots(game, 1 << (1 - stalled), &[0; HOLDEM_SEATS], now);
+    let game_type = game.game_type();
+    game.set_type_and_state(game_type, GameState::Completed);
+    
+    Ok(())
+}
+
+fn require_holdem(game: &GameAccountOptimized) -> Result<()> {
+    require!(
+        game.game_type() == GameType::MultiRound,
+        GameError::InvalidGameType
+    );
+    require!(
+        game.variant() == GameVariant::Holdem,
+        GameError::InvalidVariant
+    );
+    Ok(())
+}
+
+fn seat_of(game: &GameAccountOptimized, player: &Pubkey) -> Result<usize> {
+    let seat = game.players[..game.player_count as usize]
+        .iter()
+        .position(|p| p == player)
+        .ok_or(GameError::UnauthorizedPlayer)?;
+    Ok(seat)
+}
+
+// Community cards that must be out before betting round `round` opens
+fn board_cards_for_round(round: u8) -> usize {
+    match round {
+        0 | 1 => 0, // Preflop
+        2 => 3,     // Flop
+        3 => 4,     // Turn
+        _ => 5,     // River
+    }
+}
+
+// Board slots of the street being dealt right now, in slot order
+fn pending_board_slots(game: &GameAccountOptimized) -> Option<Vec<usize>> {
+    let dealt = BOARD_SLOT + game.board_count as usize;
+    let needed = BOARD_SLOT + board_cards_for_round(game.current_round());
+    if dealt < needed {
+        Some((dealt..needed).collect())
+    } else {
+        None
+    }
+}
+
+fn slot_mask(slots: &[usize]) -> u16 {
+    slots.iter().fold(0, |mask, slot| mask | 1 << slot)
+}
+
+fn deal_board(game: &mut GameAccountOptimized, cards: &[u8], now: i64) {
+    let first = game.board_count as usize;
+    for card in cards.iter() {
+        game.board[game.board_count as usize] = *card;
+        game.board_count += 1;
+    }
+    
+    emit!(BoardDealt {
+        game_id: game.game_id,
+        round: game.current_round(),
+        cards: game.board[first..game.board_count as usize].to_vec(),
+        timestamp: now,
+    });
+}
+
+// Both permutations are known, so deal every position from the composed deck:
+// the hole cards, and any board cards an all-in run-out skipped
+fn resolve_holdem_showdown(game: &mut GameAccountOptimized, now: i64) {
+    let [first, second] = game.deck_reveals;
+    let deck: Vec<u8> = (0..HOLDEM_DECK_SLOTS)
+        .map(|slot| second[first[slot] as usize])
+        .collect();
+    
+    let dealt = game.board_count as usize;
+    if dealt < HOLDEM_BOARD_CARDS {
+        deal_board(game, &deck[BOARD_SLOT + dealt..], now);
+    }
+    
+    let board = game.board;
+    let mut hole_cards = [[0u8; HOLDEM_HOLE_CARDS]; HOLDEM_SEATS];
+    for (seat, hole) in hole_cards.iter_mut().enumerate() {
+        let offset = seat * HOLDEM_HOLE_CARDS;
+        hole.copy_from_slice(&deck[offset..offset + HOLDEM_HOLE_CARDS]);
+    }
+    let scores = hole_cards.map(|hole| {
+        best_hand(&[hole[0], hole[1], board[0], board[1], board[2], board[3], board[4]])
+    });
+    
+    let contenders = (1u16 << HOLDEM_SEATS) - 1;
+    settle_pots(game, contenders, &scores, now);
+    let winner = if game.has_winner == 1 { Some(game.winner) } else { None };
+    
+    let game_type = game.game_type();
+    game.set_type_and_state(game_type, GameState::Completed);
+    
+    emit!(HoldemShowdown {
+        game_id: game.game_id,
+        hole_cards,
+        board,
+        hand_categories: scores.map(hand_category),
+        winner,
+        timestamp: now,
+    });
+}
+
+#[derive(Accounts)]
+pub struct RevealBoardCards<'info> {
+    #[account(mut)]
+    pub game: AccountLoader<'info, GameAccountOptimized>,
+    
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevealDeck<'info> {
+    #[account(mut)]
+    pub game: AccountLoader<'info, GameAccountOptimized>,
+    
+    pub player: Signer<'info>,
+}
@@ -1,8 +1,15 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use crate::state::{ConfigurationAccount, GameAccountOptimized, GameType, GameState};
+use crate::state::{
+    ConfigurationAccount, GameAccountOptimized, GameType, GameState, FLAG_FEES_DISTRIBUTED,
+    FLAG_WINNINGS_CLAIMED,
+};
 use crate::constants::*;
-use crate::errors::GameError;
+use crate::errors::{GameError, TreasuryError};
+use crate::events::{WinningsClaimed, FeesCollected};
+use crate::instructions::multi_round::settle_showdown_timeout;
+use crate::instructions::simple_duel::settle_phase_timeout;
+use crate::instructions::treasury::{calculate_platform_fee, rake_taken, transfer_from_vault};
 
 pub fn enter_lottery(ctx: Context<EnterLottery>, num_tickets: u32) -> Result<()> {
     let mut game = ctx.accounts.game.load_mut()?;
@@ -26,6 +33,12 @@ pub fn enter_lottery(ctx: Context<EnterLottery>, num_tickets: u32) -> Result<()>
         GameError::InvalidConfig
     );
     
+    // Every ticket takes an entry, up to the cap the creator set
+    require!(
+        game.player_count as u32 + num_tickets <= game.max_players as u32,
+        GameError::GameFull
+    );
+    
     // Calculate total cost
     let total_cost = LOTTERY_TICKET_PRICE
         .checked_mul(num_tickets as u64)
@@ -45,9 +58,6 @@ pub fn enter_lottery(ctx: Context<EnterLottery>, num_tickets: u32) -> Result<()>
     
     // Add tickets (each entry is one ticket)
     for _ in 0..num_tickets {
-        if game.player_count as usize >= MAX_PLAYERS {
-            break;
-        }
         let player_index = game.player_count as usize;
         game.players[player_index] = ctx.accounts.player.key();
         game.stakes[player_index] = LOTTERY_TICKET_PRICE;
@@ -116,42 +126,138 @@ pub fn draw_lottery(ctx: Context<DrawLottery>) -> Result<()> {
 }
 
 pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
-    let game = ctx.accounts.game.load()?;
+    let game_key = ctx.accounts.game.key();
+    let mut game = ctx.accounts.game.load_mut()?;
     let config = &ctx.accounts.config;
+    let clock = Clock::get()?;
+    
+    let player_key = ctx.accounts.player.key();
+    let (winnings, treasury_amount) = take_winnings(&mut game, config, &player_key)?;
     
+    // Transfer winnings from vault to winner
+    transfer_from_vault(
+        &game_key,
+        game.vault_bump,
+        &ctx.accounts.vault,
+        &ctx.accounts.player.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        winnings,
+    )?;
+    
+    emit!(WinningsClaimed {
+        game_id: game.game_id,
+        player: player_key,
+        amount: winnings,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    // Transfer platform fee to treasury
+    if treasury_amount > 0 {
+        transfer_from_vault(
+            &game_key,
+            game.vault_bump,
+            &ctx.accounts.vault,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program.to_account_info(),
+            treasury_amount,
+        )?;
+        
+        emit!(FeesCollected {
+            game_id: game.game_id,
+            treasury: ctx.accounts.treasury.key(),
+            amount: treasury_amount,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+    
+    Ok(())
+}
+
+/// Take a player's winnings out of a completed game and record the claim.
+///
+/// Returns the player's share after the platform fee and the amount owed to
+/// the treasury alongside it; the caller moves the lamports. Every seat or
+/// winner can take their winnings once.
+pub(crate) fn take_winnings(
+    game: &mut GameAccountOptimized,
+    config: &ConfigurationAccount,
+    player: &Pubkey,
+) -> Result<(u64, u64)> {
     // Game must be completed
     require!(
         game.game_state() == GameState::Completed,
         GameError::InvalidGameState
     );
     
+    // MultiRound pots are paid per seat, so side-pot winners claim too
+    if game.game_type() == GameType::MultiRound {
+        let player_index = game.players[..game.player_count as usize]
+            .iter()
+            .position(|p| p == player)
+            .ok_or(GameError::UnauthorizedPlayer)?;
+        require!(
+            game.claimed_mask & (1 << player_index) == 0,
+            TreasuryError::WinningsAlreadyClaimed
+        );
+        let payout = game.payouts[player_index];
+        require!(payout > 0, GameError::UnauthorizedPlayer);
+        
+        // Forfeited bonds owed to the treasury go out with the first claim,
+        // so they count toward the recorded fees but not toward the rake cap
+        let penalty_paid = game.get_flag(FLAG_FEES_DISTRIBUTED);
+        let platform_fee = calculate_platform_fee(
+            config,
+            config.fee_bps(GameType::MultiRound),
+            payout,
+            rake_taken(game),
+        );
+        let winnings = payout - platform_fee;
+        game.payouts[player_index] = 0;
+        game.claimed_mask |= 1 << player_index;
+        
+        let penalty = if penalty_paid { 0 } else { game.penalty_amount };
+        let treasury_amount = platform_fee
+            .checked_add(penalty)
+            .ok_or(GameError::ArithmeticOverflow)?;
+        record_fees(game, treasury_amount)?;
+        
+        return Ok((winnings, treasury_amount));
+    }
+    
     // Player must be the winner
     require!(
-        game.has_winner == 1 && ctx.accounts.player.key() == game.winner,
+        game.has_winner == 1 && *player == game.winner,
         GameError::UnauthorizedPlayer
     );
+    require!(
+        !game.get_flag(FLAG_WINNINGS_CLAIMED),
+        TreasuryError::WinningsAlreadyClaimed
+    );
     
     // Calculate winnings (pot minus platform fee)
-    let platform_fee = game.pot_total
-        .checked_mul(config.platform_fee as u64)
-        .ok_or(GameError::ArithmeticOverflow)?
-        .checked_div(100)
+    let platform_fee = calculate_platform_fee(config, config.fee_bps(game.game_type()), game.pot_total, 0);
+    
+    // Forfeited bonds owed to the treasury are paid on top of the pot
+    let treasury_amount = platform_fee
+        .checked_add(game.penalty_amount)
         .ok_or(GameError::ArithmeticOverflow)?;
     
     let winnings = game.pot_total
         .checked_sub(platform_fee)
         .ok_or(GameError::ArithmeticOverflow)?;
+    game.set_flag(FLAG_WINNINGS_CLAIMED, true);
+    record_fees(game, treasury_amount)?;
     
-    // Transfer winnings from vault to winner
-    **ctx.accounts.vault.try_borrow_mut_lamports()? -= winnings;
-    **ctx.accounts.player.try_borrow_mut_lamports()? += winnings;
-    
-    // Transfer platform fee to treasury
-    if platform_fee > 0 {
-        **ctx.accounts.vault.try_borrow_mut_lamports()? -= platform_fee;
-        **ctx.accounts.treasury.try_borrow_mut_lamports()? += platform_fee;
-    }
-    
+    Ok((winnings, treasury_amount))
+}
+
+// Add fees paid out of a game to its running total and mark the game-level
+// treasury share, such as forfeit penalties, as paid
+fn record_fees(game: &mut GameAccountOptimized, amount: u64) -> Result<()> {
+    game.platform_fee_collected = game.platform_fee_collected
+        .checked_add(amount)
+        .ok_or(GameError::ArithmeticOverflow)?;
+    game.set_flag(FLAG_FEES_DISTRIBUTED, true);
     Ok(())
 }
 
@@ -165,17 +271,21 @@ pub fn resolve_game(ctx: Context<ResolveGame>) -> Result<()> {
         GameError::InvalidGameState
     );
     
-    // For multi-round games, determine winner based on final reveal
-    if game.game_type() == GameType::MultiRound {
-        // Simple random selection for now
-        let random_seed = clock.unix_timestamp as u64;
-        let winner_index = (random_seed % game.player_count as u64) as usize;
-        game.winner = game.players[winner_index];
-        game.has_winner = 1;
+    // Showdowns settle on reveal; this only handles players who never
+    // revealed, once the phase deadline has passed
+    match game.game_type() {
+        GameType::MultiRound => settle_showdown_timeout(
+            &mut game,
+            ctx.accounts.config.fold_penalty_recipient,
+            clock.unix_timestamp,
+        )?,
+        GameType::SimpleDuel => settle_phase_timeout(
+            &mut game,
+            ctx.accounts.config.fold_penalty_recipient,
+            clock.unix_timestamp,
+        )?,
+        _ => return Err(GameError::InvalidGameType.into()),
     }
-    
-    let game_type = game.game_type();
-    game.set_type_and_state(game_type, GameState::Completed);
     let current_time = clock.unix_timestamp as u32;
     let start_time = game.start_time();
     game.set_timestamps(start_time, current_time);
@@ -188,8 +298,12 @@ pub struct EnterLottery<'info> {
     #[account(mut)]
     pub game: AccountLoader<'info, GameAccountOptimized>,
     
-    /// CHECK: Vault account for holding stakes
-    #[account(mut)]
+    /// CHECK: Vault PDA holding this game's stakes
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump = game.load()?.vault_bump
+    )]
     pub vault: AccountInfo<'info>,
     
     #[account(mut)]
@@ -214,16 +328,22 @@ pub struct ClaimWinnings<'info> {
     #[account(seeds = [b"config"], bump)]
     pub config: Account<'info, ConfigurationAccount>,
     
-    /// CHECK: Vault account holding the pot
-    #[account(mut)]
+    /// CHECK: Vault PDA holding this game's stakes
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump = game.load()?.vault_bump
+    )]
     pub vault: AccountInfo<'info>,
     
-    /// CHECK: Treasury account for platform fees
-    #[account(mut)]
+    /// CHECK: Treasury account for platform fees, as named in the config
+    #[account(mut, address = config.treasury @ TreasuryError::InvalidTreasury)]
     pub treasury: AccountInfo<'info>,
     
     #[account(mut)]
     pub player: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -231,5 +351,8 @@ pub struct ResolveGame<'info> {
     #[account(mut)]
     pub game: AccountLoader<'info, GameAccountOptimized>,
     
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, ConfigurationAccount>,
+    
     pub player: Signer<'info>,
 }
\ No newline at end of file
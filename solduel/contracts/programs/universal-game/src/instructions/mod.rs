@@ -2,12 +2,16 @@ pub mod admin;
 pub mod game_lifecycle;
 pub mod simple_duel;
 pub mod multi_round;
+pub mod holdem;
 pub mod lottery;
+pub mod rematch;
 pub mod treasury;
 
 pub use admin::*;
 pub use game_lifecycle::*;
 pub use simple_duel::*;
 pub use multi_round::*;
+pub use holdem::*;
 pub use lottery::*;
+pub use rematch::*;
 pub use treasury::*;
\ No newline at end of file
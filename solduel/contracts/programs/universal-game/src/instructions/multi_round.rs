@@ -1,7 +1,23 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use crate::state::{GameAccountOptimized, GameType, GameState, BetAction};
+use crate::commitment::compute_hand_commitment;
+use crate::constants::{REVEAL_PHASE_TIMEOUT, FIXED_LIMIT_MAX_RAISES, MAX_TABLE_SEATS, VAULT_SEED};
+use crate::poker::{deal_hands, evaluate_hand, hand_category};
+use crate::state::{
+    GameAccountOptimized, ConfigurationAccount, GameType, GameState, GameVariant, BetAction,
+    BettingStructure, DrawPolicy, FoldPenaltyRecipient, FLAG_IS_DRAW, FLAG_HAS_TIMEOUT,
+};
 use crate::errors::GameError;
+use crate::instructions::game_lifecycle::share_forfeited_stakes;
+use crate::instructions::holdem::betting_open;
+use crate::instructions::treasury::forfeit_bond;
+use crate::events::{
+    BetPlaced, HandRevealed, ShowdownResolved, GameDrawn, PlayerTimedOut, PlayerFolded,
+    PotAwarded, ActionTimedOut,
+};
+
+/// Marks a history entry the game recorded for a player whose clock ran out
+const TIMED_OUT_ACTION: u8 = 0x80;
 
 pub fn place_bet(ctx: Context<PlaceBet>, action: BetAction) -> Result<()> {
     let mut game = ctx.accounts.game.load_mut()?;
@@ -24,49 +40,60 @@ pub fn place_bet(ctx: Context<PlaceBet>, action: BetAction) -> Result<()> {
         .position(|p| p == &ctx.accounts.player.key())
         .ok_or(GameError::UnauthorizedPlayer)?;
     
+    // Hold'em streets only open once their cards are dealt
+    if game.variant() == GameVariant::Holdem {
+        require!(betting_open(&game), GameError::CardsNotDealt);
+    }
+    
+    // Players act in seat order; all-in players sit out the betting
+    require!(
+        game.all_in_mask & (1 << player_index) == 0,
+        GameError::PlayerAllIn
+    );
+    require!(
+        player_index == game.turn_index as usize,
+        GameError::NotPlayerTurn
+    );
+    
+    let current_bet = calculate_current_bet(&game)?;
+    let to_call = current_bet.saturating_sub(game.stakes[player_index]);
+    
     // Process betting action
     match action {
         BetAction::Check => {
-            // Check is allowed if no bet is pending
-            let action_count = game.action_count;
-            if action_count < 50 {
-                game.action_history_packed[action_count as usize] = 0; // Check = 0
-                game.action_count += 1;
-            }
+            // Check is only allowed if no bet is pending
+            require!(to_call == 0, GameError::CannotCheckFacingBet);
+            record_action(&mut game, 0); // Check = 0
         },
         BetAction::Call => {
             // Match the current bet
-            let current_bet = calculate_current_bet(&*game)?;
-            let player_bet = game.stakes[player_index];
-            let call_amount = current_bet.saturating_sub(player_bet);
+            require!(to_call > 0, GameError::NothingToCall);
             
-            if call_amount > 0 {
-                // Transfer additional stake
-                system_program::transfer(
-                    CpiContext::new(
-                        ctx.accounts.system_program.to_account_info(),
-                        system_program::Transfer {
-                            from: ctx.accounts.player.to_account_info(),
-                            to: ctx.accounts.vault.to_account_info(),
-                        },
-                    ),
-                    call_amount,
-                )?;
-                
-                game.stakes[player_index] += call_amount;
-                game.pot_total += call_amount;
-            }
+            // Transfer additional stake
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.player.to_account_info(),
+                        to: ctx.accounts.vault.to_account_info(),
+                    },
+                ),
+                to_call,
+            )?;
             
-            let action_count = game.action_count;
-            if action_count < 50 {
-                game.action_history_packed[action_count as usize] = 1; // Call = 1
-                game.action_count += 1;
-            }
+            game.stakes[player_index] += to_call;
+            game.pot_total += to_call;
+            record_action(&mut game, 1); // Call = 1
         },
         BetAction::Raise(amount) => {
-            // Raise the bet
-            let current_bet = calculate_current_bet(&*game)?;
+            // Raise the bet to `amount`, sized per the game's betting structure
             require!(amount > current_bet, GameError::InvalidBetAction);
+            require!(
+                amount <= ctx.accounts.config.max_stake,
+                GameError::StakeTooHigh
+            );
+            let increment = amount - current_bet;
+            validate_raise(&game, to_call, increment)?;
             
             let raise_amount = amount - game.stakes[player_index];
             
@@ -84,68 +111,575 @@ pub fn place_bet(ctx: Context<PlaceBet>, action: BetAction) -> Result<()> {
             
             game.stakes[player_index] += raise_amount;
             game.pot_total += raise_amount;
-            let action_count = game.action_count;
-            if action_count < 50 {
-                game.action_history_packed[action_count as usize] = 2; // Raise = 2
-                game.action_count += 1;
-            }
+            game.min_raise = increment;
+            game.round_raises += 1;
+            
+            // Everyone else has to respond to the raise
+            game.acted_mask = 0;
+            record_action(&mut game, 2); // Raise = 2
         },
         BetAction::Fold => {
-            // Folding forfeits the game
-            require!(
-                game.current_round() < game.max_rounds(),
-                GameError::CannotFoldFinalRound
-            );
+            // Folding drops the player from the hand, in any round; their
+            // stake stays in the pot and the hand ends once one player is left
+            record_action(&mut game, 3); // Fold = 3
+            fold(&mut game, player_index, &ctx.accounts.config, clock.unix_timestamp)?;
+        },
+        BetAction::AllIn => {
+            // Commit everything the player can spend, up to the most the
+            // betting structure allows and the other contenders could match;
+            // the wallet keeps its rent-exempt minimum
+            let limit = max_raise_to(&game, current_bet, to_call)
+                .min(matchable_stake(&game, player_index, ctx.accounts.config.max_stake))
+                .saturating_sub(game.stakes[player_index]);
+            require!(limit > 0, GameError::InvalidBetAction);
+            let rent_floor = Rent::get()?.minimum_balance(0);
+            let spendable = ctx.accounts.player.lamports().saturating_sub(rent_floor);
+            require!(spendable > 0, GameError::InsufficientFunds);
+            let amount = spendable.min(limit);
+            
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.player.to_account_info(),
+                        to: ctx.accounts.vault.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+            
+            game.stakes[player_index] += amount;
+            game.pot_total += amount;
+            if amount == spendable {
+                game.all_in_mask |= 1 << player_index;
+            }
             
-            // Other player wins
-            let winner_index = if player_index == 0 { 1 } else { 0 };
-            game.winner = game.players[winner_index];
-            game.has_winner = 1;
-            let game_type = game.game_type();
-            game.set_type_and_state(game_type, GameState::Completed);
-            let current_time = clock.unix_timestamp as u32;
-            let start_time = game.start_time();
-    game.set_timestamps(start_time, current_time);
-            let action_count = game.action_count;
-            if action_count < 50 {
-                game.action_history_packed[action_count as usize] = 3; // Fold = 3
-                game.action_count += 1;
+            // Going over the current bet is a raise; a short all-in raise
+            // must still be called but does not reopen the betting
+            let new_stake = game.stakes[player_index];
+            if new_stake > current_bet && new_stake - current_bet >= game.min_raise {
+                game.min_raise = new_stake - current_bet;
+                game.round_raises += 1;
+                game.acted_mask = 0;
             }
+            record_action(&mut game, 4); // AllIn = 4
         },
     }
     
-    let current_time = clock.unix_timestamp as u32;
+    emit!(BetPlaced {
+        game_id: game.game_id,
+        player: ctx.accounts.player.key(),
+        action,
+        round: game.current_round(),
+        pot_total: game.pot_total,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    end_turn(&mut game, player_index, clock.unix_timestamp);
+    
+    Ok(())
+}
+
+pub fn enforce_action_timeout(ctx: Context<EnforceActionTimeout>) -> Result<()> {
+    let mut game = ctx.accounts.game.load_mut()?;
+    let clock = Clock::get()?;
+    
+    settle_action_timeout(&mut game, &ctx.accounts.config, clock.unix_timestamp)
+}
+
+/// Act for the seat whose action clock has run out.
+///
+/// The seat checks when nothing is owed and folds otherwise. Tables created
+/// without an action clock give each seat the configured timeout instead.
+pub(crate) fn settle_action_timeout(
+    game: &mut GameAccountOptimized,
+    config: &ConfigurationAccount,
+    now: i64,
+) -> Result<()> {
+    require!(
+        game.game_state() == GameState::Active,
+        GameError::InvalidGameState
+    );
+    require!(
+        game.game_type() == GameType::MultiRound,
+        GameError::InvalidGameType
+    );
+    // A Hold'em street stalled on dealing is settled through force_finish
+    if game.variant() == GameVariant::Holdem {
+        require!(betting_open(game), GameError::CardsNotDealt);
+    }
+    
+    // The clock runs from the last move made at the table
+    let deadline = game.last_action_time() as i64 + action_clock(game, config);
+    require!(
+        now > deadline,
+        GameError::DeadlineNotReached
+    );
+    
+    // Check when nothing is owed, otherwise fold
+    let seat = game.turn_index as usize;
+    let current_bet = calculate_current_bet(game)?;
+    let (action, code) = if game.stakes[seat] >= current_bet {
+        (BetAction::Check, 0)
+    } else {
+        (BetAction::Fold, 3)
+    };
+    record_action(game, code | TIMED_OUT_ACTION);
+    if action == BetAction::Fold {
+        fold(game, seat, config, now)?;
+    }
+    
+    emit!(ActionTimedOut {
+        game_id: game.game_id,
+        player: game.players[seat],
+        action,
+        round: game.current_round(),
+        timestamp: now,
+    });
+    
+    end_turn(game, seat, now);
+    
+    Ok(())
+}
+
+pub fn reveal_hand(ctx: Context<RevealHand>, secret: [u8; 32]) -> Result<()> {
+    let mut game = ctx.accounts.game.load_mut()?;
+    let clock = Clock::get()?;
+    
+    require!(
+        game.game_type() == GameType::MultiRound,
+        GameError::InvalidGameType
+    );
+    require!(
+        game.game_state() == GameState::Resolving,
+        GameError::InvalidGameState
+    );
+    
+    // Hold'em showdowns reveal the deck instead
+    require!(
+        game.variant() != GameVariant::Holdem,
+        GameError::InvalidVariant
+    );
+    
+    let player_key = ctx.accounts.player.key();
+    let player_index = game.players[..game.player_count as usize]
+        .iter()
+        .position(|p| p == &player_key)
+        .ok_or(GameError::UnauthorizedPlayer)?;
+    require!(
+        game.folded_mask & (1 << player_index) == 0,
+        GameError::PlayerFolded
+    );
+    require!(
+        game.reveals_packed[player_index] == 0,
+        GameError::MoveAlreadySubmitted
+    );
+    
+    // Secret must match the commitment made when taking the seat
+    let commitment = compute_hand_commitment(ctx.program_id, game.game_id, &player_key, &secret);
+    require!(
+        commitment == game.commit_hashes[player_index],
+        GameError::InvalidReveal
+    );
+    
+    // Every revealed secret feeds the deal, so no single player controls the
+    // cards. Withholding one to re-deal forfeits the withholder's stake.
+    for (acc, byte) in game.vrf_result.iter_mut().zip(secret.iter()) {
+        *acc ^= byte;
+    }
+    game.reveals_packed[player_index] = 1;
+    
     let start_time = game.start_time();
-    game.set_timestamps(start_time, current_time);
+    game.set_timestamps(start_time, clock.unix_timestamp as u32);
+    
+    emit!(HandRevealed {
+        game_id: game.game_id,
+        player: player_key,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    // Folded players have nothing to show
+    let all_revealed = (0..game.player_count as usize)
+        .all(|i| game.folded_mask & (1 << i) != 0 || game.reveals_packed[i] != 0);
+    if all_revealed {
+        resolve_showdown(&mut game, clock.unix_timestamp);
+    }
+    
+    Ok(())
+}
+
+/// Settle a MultiRound showdown whose reveal deadline has passed.
+///
+/// Players who did not reveal forfeit their stake and penalty bond; the
+/// showdown is played among those who did. If nobody revealed, the stakes
+/// are returned through `claim_draw` and no further bond is forfeited; seats
+/// that folded stay out, so their stakes go to the others.
+pub(crate) fn settle_showdown_timeout(
+    game: &mut GameAccountOptimized,
+    recipient: FoldPenaltyRecipient,
+    now: i64,
+) -> Result<()> {
+    require!(
+        now > game.phase_deadline as i64,
+        GameError::DeadlineNotReached
+    );
+    
+    let (revealed, stalled): (Vec<usize>, Vec<usize>) = (0..game.player_count as usize)
+        .filter(|i| game.folded_mask & (1 << i) == 0)
+        .partition(|i| game.reveals_packed[*i] != 0);
+    for i in stalled.into_iter() {
+        game.set_eliminated(i);
+        let penalty = if revealed.is_empty() { 0 } else { forfeit_bond(game, i, recipient)? };
+        
+        emit!(PlayerTimedOut {
+            game_id: game.game_id,
+            player: game.players[i],
+            penalty_amount: penalty,
+            timestamp: now,
+        });
+    }
+    game.set_flag(FLAG_HAS_TIMEOUT, true);
+    
+    if revealed.is_empty() {
+        game.eliminated_mask = 0;
+        share_forfeited_stakes(game);
+        game.set_draw_policy(DrawPolicy::Refund);
+        game.set_flag(FLAG_IS_DRAW, true);
+        let game_type = game.game_type();
+        game.set_type_and_state(game_type, GameState::Completed);
+        
+        emit!(GameDrawn {
+            game_id: game.game_id,
+            policy: DrawPolicy::Refund,
+            timestamp: now,
+        });
+    } else if game.variant() == GameVariant::Holdem {
+        // Heads-up, so the only seat that revealed takes the pots
+        let contenders = contenders(game);
+        settle_pots(game, contenders, &[0; MAX_TABLE_SEATS], now);
+        let game_type = game.game_type();
+        game.set_type_and_state(game_type, GameState::Completed);
+    } else {
+        resolve_showdown(game, now);
+    }
+    
+    Ok(())
+}
+
+// Fold a seat and forfeit its penalty bond to the configured recipient. The
+// folded stake stays in the pot. The last player left wins every pot they are
+// eligible for.
+fn fold(
+    game: &mut GameAccountOptimized,
+    seat: usize,
+    config: &ConfigurationAccount,
+    now: i64,
+) -> Result<()> {
+    game.folded_mask |= 1 << seat;
+    let penalty = forfeit_bond(game, seat, config.fold_penalty_recipient)?;
     
-    // Check if round is complete
-    if should_advance_round(&*game) {
-        let current_round = game.current_round();
-        let max_rounds = game.max_rounds();
+    emit!(PlayerFolded {
+        game_id: game.game_id,
+        player: game.players[seat],
+        penalty_amount: penalty,
+        recipient: config.fold_penalty_recipient,
+        timestamp: now,
+    });
+    
+    let remaining = contenders(game);
+    if remaining.count_ones() == 1 {
+        settle_pots(game, remaining, &[0; MAX_TABLE_SEATS], now);
+        let game_type = game.game_type();
+        game.set_type_and_state(game_type, GameState::Completed);
+    }
+    
+    Ok(())
+}
+
+// Pass the action on from `seat` and close the betting round once everyone
+// still betting has acted
+fn end_turn(game: &mut GameAccountOptimized, seat: usize, now: i64) {
+    game.acted_mask |= 1 << seat;
+    game.turn_index = next_to_act(game, seat);
+    
+    let start_time = game.start_time();
+    game.set_timestamps(start_time, now as u32);
+    
+    if game.game_state() != GameState::Active || !betting_round_closed(game) {
+        return;
+    }
+    
+    let holdem = game.variant() == GameVariant::Holdem;
+    let current_round = game.current_round();
+    let max_rounds = game.max_rounds();
+    
+    if current_round + 1 > max_rounds || !betting_continues(game) {
+        // Betting is over - players reveal their hand secrets for the showdown
+        let game_type = game.game_type();
+        game.set_type_and_state(game_type, GameState::Resolving);
+        game.phase_deadline = (now + REVEAL_PHASE_TIMEOUT) as u32;
+    } else {
+        // The button stays put for the whole hand and moves on at a rematch
         game.set_rounds(current_round + 1, max_rounds);
+        open_betting_round(game);
+        
+        // Hold'em deals the next street before betting resumes
+        if holdem {
+            game.phase_deadline = (now + REVEAL_PHASE_TIMEOUT) as u32;
+        }
+    }
+}
+
+// Deal a hand to every player still in and award each pot to the
+// strongest hand eligible for it
+fn resolve_showdown(game: &mut GameAccountOptimized, now: i64) {
+    let hands = deal_hands(&game.vrf_result, game.player_count as usize);
+    let scores: Vec<u32> = hands.iter().map(evaluate_hand).collect();
+    
+    let contenders = contenders(game);
+    settle_pots(game, contenders, &scores, now);
+    
+    let game_type = game.game_type();
+    game.set_type_and_state(game_type, GameState::Completed);
+    
+    emit!(ShowdownResolved {
+        game_id: game.game_id,
+        hands,
+        hand_categories: scores.iter().map(|s| hand_category(*s)).collect(),
+        winner: if game.has_winner == 1 { Some(game.winner) } else { None },
+        timestamp: now,
+    });
+}
+
+/// Split the pot into a main pot and side pots and record each seat's winnings.
+///
+/// Each pot is one contribution level: every player pays into it up to their
+/// own stake, and only contenders who staked at least that level can win it.
+/// The best score among them takes the pot, with ties shared and any odd
+/// lamport going to the earliest seat. The main pot winner becomes the game
+/// winner when there is exactly one.
+pub(crate) fn settle_pots(
+    game: &mut GameAccountOptimized,
+    contenders: u16,
+    scores: &[u32],
+    now: i64,
+) {
+    let seats = game.player_count as usize;
+    let stakes = game.stakes;
+    let mut levels: Vec<u64> = stakes[..seats].to_vec();
+    levels.sort_unstable();
+    levels.dedup();
+    
+    let is_contender = |i: usize| contenders & (1 << i) != 0;
+    let mut payouts = [0u64; MAX_TABLE_SEATS];
+    let mut floor = 0;
+    let mut main_pot_winners: Option<Vec<usize>> = None;
+    
+    for (pot_index, level) in levels.into_iter().enumerate() {
+        let amount: u64 = stakes[..seats]
+            .iter()
+            .map(|stake| (*stake).min(level) - (*stake).min(floor))
+            .sum();
+        floor = level;
+        if amount == 0 {
+            continue;
+        }
+        
+        // Money nobody still in can claim goes to every contender
+        let mut eligible: Vec<usize> = (0..seats)
+            .filter(|i| is_contender(*i) && stakes[*i] >= level)
+            .collect();
+        if eligible.is_empty() {
+            eligible = (0..seats).filter(|i| is_contender(*i)).collect();
+        }
+        let best = eligible.iter().map(|i| scores[*i]).max().unwrap_or(0);
+        let winners: Vec<usize> = eligible.into_iter().filter(|i| scores[*i] == best).collect();
+        if winners.is_empty() {
+            continue;
+        }
+        
+        let share = amount / winners.len() as u64;
+        for i in winners.iter() {
+            payouts[*i] += share;
+        }
+        payouts[winners[0]] += amount - share * winners.len() as u64;
+        
+        emit!(PotAwarded {
+            game_id: game.game_id,
+            pot_index: pot_index as u8,
+            amount,
+            winners: winners.iter().map(|i| game.players[*i]).collect(),
+            timestamp: now,
+        });
         
-        if current_round + 1 > max_rounds {
-            // Game complete - determine winner based on random reveal
-            let game_type = game.game_type();
-            game.set_type_and_state(game_type, GameState::Resolving);
+        if main_pot_winners.is_none() {
+            main_pot_winners = Some(winners);
+        }
+    }
+    
+    game.payouts = payouts;
+    if let Some(winners) = main_pot_winners.filter(|w| w.len() == 1) {
+        game.winner = game.players[winners[0]];
+        game.has_winner = 1;
+    }
+}
+
+/// Open a betting round: action starts left of the button, except heads-up
+/// Hold'em where the button acts first preflop
+pub(crate) fn open_betting_round(game: &mut GameAccountOptimized) {
+    let dealer = game.dealer_index as usize;
+    let button_first = game.variant() == GameVariant::Holdem && game.current_round() <= 1;
+    
+    game.acted_mask = 0;
+    game.turn_index = if button_first && can_act(game, dealer) {
+        dealer as u8
+    } else {
+        next_to_act(game, dealer)
+    };
+    game.min_raise = game.entry_fee;
+    game.round_raises = 0;
+}
+
+// Players still in the hand: not folded and not forfeited (bit per seat)
+fn contenders(game: &GameAccountOptimized) -> u16 {
+    let seats = (1u16 << game.player_count) - 1;
+    seats & !game.folded_mask & !game.eliminated_mask
+}
+
+// Whether a seat can still bet: in the hand and not all-in
+fn can_act(game: &GameAccountOptimized, seat: usize) -> bool {
+    contenders(game) & (1 << seat) != 0 && game.all_in_mask & (1 << seat) == 0
+}
+
+// Next seat after `from` that can still bet
+fn next_to_act(game: &GameAccountOptimized, from: usize) -> u8 {
+    let seats = game.player_count as usize;
+    (1..=seats)
+        .map(|step| (from + step) % seats)
+        .find(|seat| can_act(game, *seat))
+        .unwrap_or(from) as u8
+}
+
+// Further betting rounds need at least two players who can still bet
+fn betting_continues(game: &GameAccountOptimized) -> bool {
+    (0..game.player_count as usize).filter(|seat| can_act(game, *seat)).count() > 1
+}
+
+fn validate_raise(game: &GameAccountOptimized, to_call: u64, increment: u64) -> Result<()> {
+    match game.betting_structure() {
+        BettingStructure::NoLimit => {
+            require!(increment >= game.min_raise, GameError::RaiseTooSmall);
+        }
+        BettingStructure::FixedLimit => {
+            require!(
+                game.round_raises < FIXED_LIMIT_MAX_RAISES,
+                GameError::RaiseCapReached
+            );
+            require!(
+                increment == fixed_limit_bet(game),
+                GameError::InvalidFixedLimitRaise
+            );
+        }
+        BettingStructure::PotLimit => {
+            require!(increment >= game.min_raise, GameError::RaiseTooSmall);
+            
+            // The largest raise is the size of the pot once the raiser has called
+            let pot_after_call = game.pot_total
+                .checked_add(to_call)
+                .ok_or(GameError::ArithmeticOverflow)?;
+            require!(increment <= pot_after_call, GameError::RaiseExceedsPotLimit);
         }
     }
     
     Ok(())
 }
 
+// Highest total stake the betting structure allows this player to reach
+fn max_raise_to(game: &GameAccountOptimized, current_bet: u64, to_call: u64) -> u64 {
+    match game.betting_structure() {
+        BettingStructure::NoLimit => u64::MAX,
+        BettingStructure::PotLimit => {
+            current_bet.saturating_add(game.pot_total.saturating_add(to_call))
+        }
+        BettingStructure::FixedLimit => {
+            if game.round_raises < FIXED_LIMIT_MAX_RAISES {
+                current_bet.saturating_add(fixed_limit_bet(game))
+            } else {
+                current_bet
+            }
+        }
+    }
+}
+
+// Largest total stake another contender could still match: an all-in
+// player has already put in all they can, anyone else is held to the
+// configured maximum stake
+fn matchable_stake(game: &GameAccountOptimized, seat: usize, max_stake: u64) -> u64 {
+    let others = contenders(game) & !(1 << seat);
+    (0..game.player_count as usize)
+        .filter(|i| others & (1 << i) != 0)
+        .map(|i| if game.all_in_mask & (1 << i) != 0 { game.stakes[i] } else { max_stake })
+        .max()
+        .unwrap_or(0)
+}
+
+// Fixed-limit bet unit: the entry fee in the first half of the game,
+// doubled in the later rounds
+fn fixed_limit_bet(game: &GameAccountOptimized) -> u64 {
+    if game.current_round() * 2 <= game.max_rounds() {
+        game.entry_fee
+    } else {
+        game.entry_fee.saturating_mul(2)
+    }
+}
+
+// Seconds a seat has to act: the table's own clock, or the configured
+// timeout for tables created without one
+fn action_clock(game: &GameAccountOptimized, config: &ConfigurationAccount) -> i64 {
+    if game.action_timeout > 0 {
+        game.action_timeout as i64
+    } else {
+        config.timeout as i64
+    }
+}
+
+fn record_action(game: &mut GameAccountOptimized, code: u8) {
+    let action_count = game.action_count;
+    if action_count < 50 {
+        game.action_history_packed[action_count as usize] = code;
+        game.action_count += 1;
+    }
+}
+
 fn calculate_current_bet(game: &GameAccountOptimized) -> Result<u64> {
-    // Find the highest stake among active players
-    Ok(game.stakes[..game.player_count as usize].iter().max().copied().unwrap_or(0))
+    // Find the highest stake among players still in; a folded stake can
+    // outgrow it once the bond is forfeited into the pot
+    let contenders = contenders(game);
+    Ok((0..game.player_count as usize)
+        .filter(|i| contenders & (1 << i) != 0)
+        .map(|i| game.stakes[i])
+        .max()
+        .unwrap_or(0))
 }
 
-fn should_advance_round(game: &GameAccountOptimized) -> bool {
-    // Round advances when all players have acted equally
-    // This is simplified - real implementation would track betting rounds properly
+fn betting_round_closed(game: &GameAccountOptimized) -> bool {
+    // Round closes once every player who can still bet has acted since the
+    // last raise and matched the highest stake
     if game.player_count == 0 {
         return false;
     }
-    game.action_count % game.player_count == 0
+    let current_bet = calculate_current_bet(game).unwrap_or(0);
+    let able: Vec<usize> = (0..game.player_count as usize)
+        .filter(|seat| can_act(game, *seat))
+        .collect();
+    
+    // A lone player left to bet only has to cover what the all-in players put in
+    if able.len() <= 1 {
+        return able.iter().all(|seat| game.stakes[*seat] >= current_bet);
+    }
+    able.iter().all(|seat| {
+        game.acted_mask & (1 << seat) != 0 && game.stakes[*seat] == current_bet
+    })
 }
 
 #[derive(Accounts)]
@@ -153,12 +687,132 @@ pub struct PlaceBet<'info> {
     #[account(mut)]
     pub game: AccountLoader<'info, GameAccountOptimized>,
     
-    /// CHECK: Vault account for holding stakes
-    #[account(mut)]
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, ConfigurationAccount>,
+    
+    /// CHECK: Vault PDA holding this game's stakes
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump = game.load()?.vault_bump
+    )]
     pub vault: AccountInfo<'info>,
     
     #[account(mut)]
     pub player: Signer<'info>,
     
     pub system_program: Program<'info, System>,
-}
\ No newline at end of file
+}
+
+#[derive(Accounts)]
+pub struct EnforceActionTimeout<'info> {
+    #[account(mut)]
+    pub game: AccountLoader<'info, GameAccountOptimized>,
+    
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, ConfigurationAccount>,
+    
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevealHand<'info> {
+    #[account(mut)]
+    pub game: AccountLoader<'info, GameAccountOptimized>,
+    
+    pub player: Signer<'info>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::Zeroable;
+    
+    // A table whose betting is over, with one seat per stake
+    fn table(stakes: &[u64]) -> Box<GameAccountOptimized> {
+        let mut game = Box::new(GameAccountOptimized::zeroed());
+        game.set_type_and_state(GameType::MultiRound, GameState::Resolving);
+        for (i, stake) in stakes.iter().enumerate() {
+            game.players[i] = Pubkey::new_unique();
+            game.stakes[i] = *stake;
+        }
+        game.player_count = stakes.len() as u8;
+        game.pot_total = stakes.iter().sum();
+        game
+    }
+    
+    #[test]
+    fn tables_without_a_clock_act_on_the_configured_timeout() {
+        let mut game = table(&[100, 200]);
+        game.set_type_and_state(GameType::MultiRound, GameState::Active);
+        let mut config = crate::state::legacy_config(0, 0);
+        config.timeout = 3600;
+        
+        assert_eq!(
+            settle_action_timeout(&mut game, &config, 3600).unwrap_err(),
+            GameError::DeadlineNotReached.into()
+        );
+        
+        // Seat 0 owes 100 when its time runs out, so it folds
+        settle_action_timeout(&mut game, &config, 3601).unwrap();
+        assert_eq!(game.folded_mask, 0b01);
+        assert!(game.game_state() == GameState::Completed);
+        assert_eq!(game.payouts[..2], [0, 300]);
+    }
+    
+    #[test]
+    fn refunded_showdowns_pay_nothing_to_folded_seats() {
+        // The folded seat's stake includes its forfeited bond
+        let mut game = table(&[350, 100, 100]);
+        game.folded_mask = 0b001;
+        
+        settle_showdown_timeout(&mut game, FoldPenaltyRecipient::Opponent, 1).unwrap();
+        assert!(game.get_flag(FLAG_IS_DRAW));
+        assert_eq!(game.stakes[..3], [0, 275, 275]);
+        assert_eq!(game.eliminated_mask, 0);
+    }
+    
+    #[test]
+    fn short_all_in_only_wins_the_main_pot() {
+        let mut game = table(&[100, 300, 300]);
+        settle_pots(&mut game, 0b111, &[9, 5, 7], 0);
+        
+        // Main pot of 3 x 100 to the short stack, side pot of 2 x 200 to the
+        // better of the two who covered it
+        assert_eq!(game.payouts[..3], [300, 0, 400]);
+        assert_eq!(game.has_winner, 1);
+        assert_eq!(game.winner, game.players[0]);
+    }
+    
+    #[test]
+    fn side_pots_stack_by_contribution_level() {
+        let mut game = table(&[50, 150, 300, 300]);
+        settle_pots(&mut game, 0b1111, &[9, 8, 1, 2], 0);
+        
+        // 200 at the 50 level, 300 more at 150 and 300 more at 300
+        assert_eq!(game.payouts[..4], [200, 300, 0, 300]);
+        assert_eq!(game.payouts.iter().sum::<u64>(), game.pot_total);
+    }
+    
+    #[test]
+    fn tied_pots_split_with_the_odd_lamport_to_the_earliest_seat() {
+        let mut game = table(&[101, 101, 101]);
+        settle_pots(&mut game, 0b111, &[4, 7, 7], 0);
+        
+        assert_eq!(game.payouts[..3], [0, 152, 151]);
+        assert_eq!(game.has_winner, 0);
+    }
+    
+    #[test]
+    fn folded_stakes_go_to_the_players_still_in() {
+        // The folded seat put in more than anyone left. Its money is shared
+        // level by level, and the level no contender reached goes to the best
+        // hand among all of them.
+        let mut game = table(&[300, 100, 200]);
+        game.folded_mask = 0b001;
+        settle_pots(&mut game, 0b110, &[9, 3, 1], 0);
+        
+        assert_eq!(game.payouts[..3], [0, 400, 200]);
+        assert_eq!(game.winner, game.players[1]);
+    }
+}
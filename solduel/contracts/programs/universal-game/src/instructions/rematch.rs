@@ -0,0 +1,370 @@
+// Rematches between the players of a completed SimpleDuel or MultiRound game.
+//
+// Either player proposes from the completed game, which creates a new game
+// with the same rules, stake and seats, with the proposer seated. A game is
+// rematched once, so only one new game can carry its players and winnings. The other
+// players accept by taking their seats, and the game starts once everyone is
+// back, with a MultiRound button one seat on from the previous hand. A player
+// with unclaimed winnings can roll them in to cover the new stake; anything
+// left over is paid out as a normal claim would. The penalty bond is posted
+// afresh, while bonds from the previous game are taken back with claim_bond.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{ConfigurationAccount, GameAccountOptimized, GameType, GameState};
+use crate::constants::*;
+use crate::errors::{GameError, TreasuryError};
+use crate::events::{
+    GameCreated, RematchProposed, RematchAccepted, WinningsClaimed, FeesCollected,
+};
+use crate::instructions::game_lifecycle::{checked_hand_commitment, seat_player};
+use crate::instructions::lottery::take_winnings;
+use crate::instructions::multi_round::open_betting_round;
+use crate::instructions::treasury::{penalty_bond, transfer_from_vault, vault_rent_reserve};
+
+pub fn propose_rematch(
+    ctx: Context<ProposeRematch>,
+    hand_commitment: Option<[u8; 32]>,
+    roll_in: bool,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let clock = Clock::get()?;
+    let player_key = ctx.accounts.player.key();
+    let previous_key = ctx.accounts.previous_game.key();
+    let mut previous = ctx.accounts.previous_game.load_mut()?;
+    let mut game = ctx.accounts.game.load_init()?;
+    
+    require_rematch_of(&previous, &player_key)?;
+    require_keys_eq!(previous.rematched_as, Pubkey::default(), GameError::AlreadyRematched);
+    let game_type = previous.game_type();
+    let hand_commitment = checked_hand_commitment(game_type, hand_commitment)?;
+    previous.rematched_as = ctx.accounts.game.key();
+    
+    // Same rules, stake and seats as the previous game; the rest starts fresh
+    let stake_amount = previous.entry_fee;
+    let now = clock.unix_timestamp as u32;
+    game.game_id = config.game_counter;
+    game.set_type_and_state(game_type, GameState::Waiting);
+    game.creator = player_key;
+    game.players[0] = player_key;
+    game.stakes[0] = stake_amount;
+    game.commit_hashes[0] = hand_commitment;
+    game.player_count = 1;
+    game.pot_total = stake_amount;
+    game.set_rounds(0, previous.max_rounds());
+    game.set_timestamps(now, now);
+    game.entry_fee = stake_amount;
+    game.treasury = config.treasury;
+    game.set_variant(previous.variant());
+    game.set_draw_policy(previous.draw_policy());
+    game.min_raise = stake_amount;
+    game.set_betting_structure(previous.betting_structure());
+    game.max_players = previous.player_count;
+    game.action_timeout = previous.action_timeout;
+    game.rematch_of = previous_key;
+    game.vault_bump = ctx.bumps.vault;
+    game.penalty_bond = penalty_bond(config, game_type, stake_amount)?;
+    
+    // The proposer opens the new vault, so they cover its rent reserve
+    let reserve = vault_rent_reserve(&ctx.accounts.vault)?;
+    let rolled_in = if roll_in {
+        roll_in_winnings(
+            &mut previous,
+            &previous_key,
+            config,
+            stake_amount,
+            &ctx.accounts.player.to_account_info(),
+            &ctx.accounts.previous_vault,
+            &ctx.accounts.vault,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program.to_account_info(),
+            clock.unix_timestamp,
+        )?
+    } else {
+        0
+    };
+    collect_stake(
+        &ctx.accounts.player,
+        &ctx.accounts.vault,
+        &ctx.accounts.system_program,
+        stake_amount - rolled_in + game.penalty_bond + reserve,
+    )?;
+    
+    let config = &mut ctx.accounts.config;
+    config.game_counter += 1;
+    
+    emit!(GameCreated {
+        game_id: game.game_id,
+        creator: player_key,
+        game_type,
+        variant: Some(game.variant()),
+        stake_amount,
+        penalty_bond: game.penalty_bond,
+        max_players: game.max_players,
+        max_rounds: game.max_rounds(),
+        draw_policy: if game_type == GameType::SimpleDuel {
+            Some(game.draw_policy())
+        } else {
+            None
+        },
+        betting_structure: if game_type == GameType::MultiRound {
+            Some(game.betting_structure())
+        } else {
+            None
+        },
+        is_private: true,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    emit!(RematchProposed {
+        previous_game_id: previous.game_id,
+        game_id: game.game_id,
+        proposer: player_key,
+        rolled_in,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+pub fn accept_rematch(
+    ctx: Context<AcceptRematch>,
+    hand_commitment: Option<[u8; 32]>,
+    roll_in: bool,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let clock = Clock::get()?;
+    let player_key = ctx.accounts.player.key();
+    let previous_key = ctx.accounts.previous_game.key();
+    let mut previous = ctx.accounts.previous_game.load_mut()?;
+    let mut game = ctx.accounts.game.load_mut()?;
+    
+    // Only players of the game being rematched may take a seat
+    require_keys_eq!(game.rematch_of, previous_key, GameError::NotInvited);
+    require_rematch_of(&previous, &player_key)?;
+    
+    seat_player(&mut game, player_key, hand_commitment, clock.unix_timestamp)?;
+    if game.game_type() == GameType::MultiRound && game.game_state() == GameState::Active {
+        pass_button(&mut game, &previous);
+    }
+    
+    let stake_amount = game.entry_fee;
+    let rolled_in = if roll_in {
+        roll_in_winnings(
+            &mut previous,
+            &previous_key,
+            config,
+            stake_amount,
+            &ctx.accounts.player.to_account_info(),
+            &ctx.accounts.previous_vault,
+            &ctx.accounts.vault,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program.to_account_info(),
+            clock.unix_timestamp,
+        )?
+    } else {
+        0
+    };
+    collect_stake(
+        &ctx.accounts.player,
+        &ctx.accounts.vault,
+        &ctx.accounts.system_program,
+        stake_amount - rolled_in + game.penalty_bond,
+    )?;
+    
+    emit!(RematchAccepted {
+        previous_game_id: previous.game_id,
+        game_id: game.game_id,
+        player: player_key,
+        rolled_in,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+// Rematches are offered from a finished duel or table to its own players
+fn require_rematch_of(previous: &GameAccountOptimized, player: &Pubkey) -> Result<()> {
+    require!(
+        previous.game_state() == GameState::Completed,
+        GameError::InvalidGameState
+    );
+    require!(
+        previous.game_type() != GameType::Lottery,
+        GameError::InvalidGameType
+    );
+    require!(
+        previous.players[..previous.player_count as usize].contains(player),
+        GameError::UnauthorizedPlayer
+    );
+    Ok(())
+}
+
+// Move the button one seat on from where it sat in the previous hand. Seats
+// are retaken in a new order, so the button follows the player, not the index.
+fn pass_button(game: &mut GameAccountOptimized, previous: &GameAccountOptimized) {
+    let next = (previous.dealer_index as usize + 1) % previous.player_count as usize;
+    let button = previous.players[next];
+    if let Some(seat) = game.players[..game.player_count as usize]
+        .iter()
+        .position(|p| *p == button)
+    {
+        game.dealer_index = seat as u8;
+        open_betting_round(game);
+    }
+}
+
+// Claim a player's winnings from the previous game, moving up to `stake` into
+// the rematch vault and the rest to the player. Returns the amount rolled in.
+#[allow(clippy::too_many_arguments)]
+fn roll_in_winnings<'info>(
+    previous: &mut GameAccountOptimized,
+    previous_key: &Pubkey,
+    config: &ConfigurationAccount,
+    stake: u64,
+    player: &AccountInfo<'info>,
+    previous_vault: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    treasury: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    now: i64,
+) -> Result<u64> {
+    let (winnings, treasury_amount) = take_winnings(previous, config, player.key)?;
+    let rolled_in = winnings.min(stake);
+    let bump = previous.vault_bump;
+    
+    transfer_from_vault(previous_key, bump, previous_vault, vault, system_program, rolled_in)?;
+    transfer_from_vault(previous_key, bump, previous_vault, player, system_program, winnings - rolled_in)?;
+    
+    emit!(WinningsClaimed {
+        game_id: previous.game_id,
+        player: player.key(),
+        amount: winnings,
+        timestamp: now,
+    });
+    
+    if treasury_amount > 0 {
+        transfer_from_vault(previous_key, bump, previous_vault, treasury, system_program, treasury_amount)?;
+        
+        emit!(FeesCollected {
+            game_id: previous.game_id,
+            treasury: treasury.key(),
+            amount: treasury_amount,
+            timestamp: now,
+        });
+    }
+    
+    Ok(rolled_in)
+}
+
+// Take the bond and whatever part of the stake was not rolled in from the
+// player's wallet
+fn collect_stake<'info>(
+    player: &Signer<'info>,
+    vault: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    
+    system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: player.to_account_info(),
+                to: vault.to_account_info(),
+            },
+        ),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+pub struct ProposeRematch<'info> {
+    #[account(mut)]
+    pub previous_game: AccountLoader<'info, GameAccountOptimized>,
+    
+    /// CHECK: Vault PDA holding the previous game's pot
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, previous_game.key().as_ref()],
+        bump = previous_game.load()?.vault_bump
+    )]
+    pub previous_vault: AccountInfo<'info>,
+    
+    #[account(
+        init,
+        payer = player,
+        space = GameAccountOptimized::LEN,
+        seeds = [
+            GAME_SEED,
+            player.key().as_ref(),
+            &config.game_counter.to_le_bytes()
+        ],
+        bump
+    )]
+    pub game: AccountLoader<'info, GameAccountOptimized>,
+    
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, ConfigurationAccount>,
+    
+    /// CHECK: Vault PDA holding this game's stakes
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump
+    )]
+    pub vault: AccountInfo<'info>,
+    
+    /// CHECK: Configured treasury, paid the platform fee on rolled-in winnings
+    #[account(mut, address = config.treasury @ TreasuryError::InvalidTreasury)]
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptRematch<'info> {
+    #[account(mut)]
+    pub previous_game: AccountLoader<'info, GameAccountOptimized>,
+    
+    /// CHECK: Vault PDA holding the previous game's pot
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, previous_game.key().as_ref()],
+        bump = previous_game.load()?.vault_bump
+    )]
+    pub previous_vault: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub game: AccountLoader<'info, GameAccountOptimized>,
+    
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, ConfigurationAccount>,
+    
+    /// CHECK: Vault PDA holding this game's stakes
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump = game.load()?.vault_bump
+    )]
+    pub vault: AccountInfo<'info>,
+    
+    /// CHECK: Configured treasury, paid the platform fee on rolled-in winnings
+    #[account(mut, address = config.treasury @ TreasuryError::InvalidTreasury)]
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
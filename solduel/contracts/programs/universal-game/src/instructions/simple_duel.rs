@@ -1,8 +1,16 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::hash::hash;
-use crate::state::{GameAccountOptimized, GameType, GameState, GameMove};
-use crate::errors::GameError;
+use anchor_lang::solana_program::hash::{hash, hashv};
+use crate::commitment::compute_move_commitment;
+use crate::state::{
+    ConfigurationAccount, GameAccountOptimized, GameType, GameState, GameMove, GameVariant,
+    DiceTieBreak, DrawPolicy, FoldPenaltyRecipient, FLAG_IS_DRAW, FLAG_FEES_DISTRIBUTED, FLAG_HAS_TIMEOUT,
+};
+use crate::constants::{
+    MAX_DICE, MAX_MOVE_NUMBER, DICE_MAX_REROLLS, COMMIT_PHASE_TIMEOUT, REVEAL_PHASE_TIMEOUT, VAULT_SEED,
+};
+use crate::errors::{GameError, TreasuryError};
 use crate::events::*;
+use crate::instructions::treasury::{calculate_platform_fee, forfeit_bond, transfer_from_vault};
 
 pub fn commit_move(ctx: Context<CommitMove>, move_hash: [u8; 32]) -> Result<()> {
     let mut game = ctx.accounts.game.load_mut()?;
@@ -29,6 +37,10 @@ pub fn commit_move(ctx: Context<CommitMove>, move_hash: [u8; 32]) -> Result<()>
         }
     }
     let player_index = player_index.ok_or(GameError::UnauthorizedPlayer)?;
+    require!(
+        !game.is_eliminated(player_index),
+        GameError::PlayerEliminated
+    );
     
     // Check if move already submitted
     require!(
@@ -36,15 +48,27 @@ pub fn commit_move(ctx: Context<CommitMove>, move_hash: [u8; 32]) -> Result<()>
         GameError::MoveAlreadySubmitted
     );
     
+    // Reject the empty sentinel and commitments already posted by anyone in
+    // this game; commitments are bound to the player and round, so a copied
+    // hash could never be revealed anyway
+    require!(
+        move_hash != [0; 32],
+        GameError::DuplicateCommitment
+    );
+    require!(
+        !game.commit_hashes[..game.player_count as usize].contains(&move_hash),
+        GameError::DuplicateCommitment
+    );
+    
     // Store commit hash
     game.commit_hashes[player_index] = move_hash;
     let start_time = game.start_time();
     game.set_timestamps(start_time, clock.unix_timestamp as u32);
     
-    // Check if all players have committed
+    // Check if all remaining players have committed
     let mut all_committed = true;
     for i in 0..game.player_count as usize {
-        if game.commit_hashes[i] == [0; 32] {
+        if !game.is_eliminated(i) && game.commit_hashes[i] == [0; 32] {
             all_committed = false;
             break;
         }
@@ -53,6 +77,7 @@ pub fn commit_move(ctx: Context<CommitMove>, move_hash: [u8; 32]) -> Result<()>
     if all_committed {
         let game_type = game.game_type();
         game.set_type_and_state(game_type, GameState::Resolving);
+        game.phase_deadline = (clock.unix_timestamp + REVEAL_PHASE_TIMEOUT) as u32;
     }
     
     // Emit event
@@ -91,62 +116,446 @@ pub fn reveal_move(
         }
     }
     let player_index = player_index.ok_or(GameError::UnauthorizedPlayer)?;
+    require!(
+        !game.is_eliminated(player_index),
+        GameError::PlayerEliminated
+    );
+    
+    // Each player reveals once per round
+    require!(
+        game.reveals_packed[player_index] == 0,
+        GameError::MoveAlreadySubmitted
+    );
+    let round = game.current_round();
     
     // Verify commitment
-    let move_bytes = game_move.try_to_vec()?;
-    let mut data_to_hash = move_bytes;
-    data_to_hash.extend_from_slice(&nonce);
-    let computed_hash = hash(&data_to_hash);
+    let computed_hash = compute_move_commitment(
+        ctx.program_id,
+        game.game_id,
+        round,
+        &player_key,
+        game_move,
+        &nonce,
+    )?;
     
     require!(
-        computed_hash.to_bytes() == game.commit_hashes[player_index],
+        computed_hash == game.commit_hashes[player_index],
         GameError::InvalidReveal
     );
     
+    // Reject moves that are not legal for this variant
+    validate_move(&game, player_index, game_move)?;
+    
+    // Fold the nonce into the shared randomness used by chance-based variants
+    for (acc, byte) in game.vrf_result.iter_mut().zip(nonce.iter()) {
+        *acc ^= byte;
+    }
+    
     // Store revealed move (pack into single byte)
-    let move_byte = match game_move {
-        GameMove::None => 0,
-        GameMove::Rock => 1,
-        GameMove::Paper => 2,
-        GameMove::Scissors => 3,
-        GameMove::Heads => 4,
-        GameMove::Tails => 5,
-        GameMove::Number(n) => 6 + n,
-    };
-    game.reveals_packed[player_index] = move_byte;
+    game.reveals_packed[player_index] = pack_move(game_move)?;
     let start_time = game.start_time();
     game.set_timestamps(start_time, clock.unix_timestamp as u32);
     
-    // Check if all players have revealed
+    // Check if all remaining players have revealed
     let mut all_revealed = true;
     for i in 0..game.player_count as usize {
-        if game.reveals_packed[i] == 0 {
+        if !game.is_eliminated(i) && game.reveals_packed[i] == 0 {
             all_revealed = false;
             break;
         }
     }
     
-    if all_revealed {
-        // Determine winner
-        determine_winner(&mut *game)?;
-        let game_type = game.game_type();
-        game.set_type_and_state(game_type, GameState::Completed);
-        let start = game.start_time();
-        game.set_timestamps(start, clock.unix_timestamp as u32);
-    }
-    
     // Emit event
     emit!(MoveRevealed {
         game_id: game.game_id,
         player: player_key,
         game_move,
-        round: game.current_round(),
+        round,
         timestamp: clock.unix_timestamp,
     });
     
+    if all_revealed {
+        if let GameVariant::BattleRoyale { .. } = game.variant() {
+            resolve_elimination_round(&mut game, clock.unix_timestamp)?;
+        } else {
+            settle_duel_round(&mut game, clock.unix_timestamp)?;
+        }
+        let start = game.start_time();
+        game.set_timestamps(start, clock.unix_timestamp as u32);
+    }
+    
+    Ok(())
+}
+
+// Score a two-player round, tally it towards the series and either settle
+// the duel or open the next round
+fn settle_duel_round(game: &mut GameAccountOptimized, now: i64) -> Result<()> {
+    let round = game.current_round();
+    let round_winner = determine_winner(game)?;
+    if let Some(idx) = round_winner {
+        game.series_wins[idx] += 1;
+    }
+    
+    emit!(RoundResolved {
+        game_id: game.game_id,
+        round,
+        winner: round_winner.map(|idx| game.players[idx]),
+        series_wins: game.series_wins,
+        timestamp: now,
+    });
+    
+    let best_of = game.max_rounds();
+    let wins_needed = best_of / 2 + 1;
+    let clinched = round_winner.filter(|idx| game.series_wins[*idx] >= wins_needed);
+    
+    if let Some(idx) = clinched {
+        game.winner = game.players[idx];
+        game.has_winner = 1;
+        let game_type = game.game_type();
+        game.set_type_and_state(game_type, GameState::Completed);
+    } else if best_of <= 1 && game.draw_policy() != DrawPolicy::SuddenDeath {
+        // Single-round duel ended in a draw; stakes are claimed via claim_draw
+        game.set_flag(FLAG_IS_DRAW, true);
+        let game_type = game.game_type();
+        game.set_type_and_state(game_type, GameState::Completed);
+        
+        emit!(GameDrawn {
+            game_id: game.game_id,
+            policy: game.draw_policy(),
+            timestamp: now,
+        });
+    } else if best_of <= 1 {
+        start_next_round(game, round, now);
+        
+        emit!(SuddenDeathStarted {
+            game_id: game.game_id,
+            round,
+            timestamp: now,
+        });
+    } else {
+        // Drawn rounds are replayed under the same round number
+        let next_round = if round_winner.is_some() { round + 1 } else { round };
+        start_next_round(game, next_round, now);
+    }
+    
+    Ok(())
+}
+
+// Resolve a battle-royale round: every survivor whose throw is beaten by a
+// throw somebody else made is knocked out. Rounds where nobody would be
+// knocked out (all the same throw) or everybody would (all three throws)
+// are replayed under the same round number.
+fn resolve_elimination_round(game: &mut GameAccountOptimized, now: i64) -> Result<()> {
+    let round = game.current_round();
+    let survivors: Vec<usize> = (0..game.player_count as usize)
+        .filter(|i| !game.is_eliminated(*i))
+        .collect();
+    let throws: Vec<GameMove> = survivors
+        .iter()
+        .map(|i| unpack_move(game.reveals_packed[*i]))
+        .collect();
+    
+    let losers: Vec<usize> = survivors
+        .iter()
+        .zip(throws.iter())
+        .filter(|(_, throw)| throws.iter().any(|other| beats(game, *other, **throw)))
+        .map(|(i, _)| *i)
+        .collect();
+    
+    if losers.is_empty() || losers.len() == survivors.len() {
+        start_next_round(game, round, now);
+        
+        emit!(RoundReplayed {
+            game_id: game.game_id,
+            round,
+            timestamp: now,
+        });
+        return Ok(());
+    }
+    
+    for i in losers.iter() {
+        game.set_eliminated(*i);
+        emit!(PlayerEliminated {
+            game_id: game.game_id,
+            player: game.players[*i],
+            round,
+            timestamp: now,
+        });
+    }
+    
+    finish_or_continue_royale(game, round, now);
+    
+    Ok(())
+}
+
+// Crown the last battle-royale survivor, or open the next round
+fn finish_or_continue_royale(game: &mut GameAccountOptimized, round: u8, now: i64) {
+    if game.survivor_count() == 1 {
+        let winner = (0..game.player_count as usize)
+            .find(|i| !game.is_eliminated(*i))
+            .unwrap_or(0);
+        game.winner = game.players[winner];
+        game.has_winner = 1;
+        let game_type = game.game_type();
+        game.set_type_and_state(game_type, GameState::Completed);
+    } else {
+        start_next_round(game, round + 1, now);
+    }
+}
+
+/// Settle a duel whose commit or reveal deadline has passed.
+///
+/// Players who acted in the current phase stay in; those who stalled forfeit
+/// and lose their penalty bond to `recipient`. In a two-player duel the
+/// remaining player wins the pot. In a battle royale the stalled players are
+/// knocked out and the round carries on among the rest. If nobody acted, the
+/// remaining stakes are returned through `claim_draw`.
+pub(crate) fn settle_phase_timeout(
+    game: &mut GameAccountOptimized,
+    recipient: FoldPenaltyRecipient,
+    now: i64,
+) -> Result<()> {
+    let state = game.game_state();
+    require!(
+        state == GameState::Active || state == GameState::Resolving,
+        GameError::InvalidGameState
+    );
+    require!(
+        now > game.phase_deadline as i64,
+        GameError::DeadlineNotReached
+    );
+    
+    // Commit phase checks commitments, reveal phase checks reveals
+    let (acted, stalled): (Vec<usize>, Vec<usize>) = (0..game.player_count as usize)
+        .filter(|i| !game.is_eliminated(*i))
+        .partition(|i| {
+            if state == GameState::Active {
+                game.commit_hashes[*i] != [0; 32]
+            } else {
+                game.reveals_packed[*i] != 0
+            }
+        });
+    
+    // Everyone acted, so the phase would already have advanced
+    require!(!stalled.is_empty(), GameError::InvalidGameState);
+    game.set_flag(FLAG_HAS_TIMEOUT, true);
+    
+    if acted.is_empty() {
+        // Nobody showed up: a duel refunds both stakes, a battle royale
+        // shares the pot among everyone still standing
+        let policy = if let GameVariant::BattleRoyale { .. } = game.variant() {
+            DrawPolicy::SplitPot
+        } else {
+            DrawPolicy::Refund
+        };
+        game.set_draw_policy(policy);
+        game.set_flag(FLAG_IS_DRAW, true);
+        let game_type = game.game_type();
+        game.set_type_and_state(game_type, GameState::Completed);
+        
+        for i in stalled.iter() {
+            emit!(PlayerTimedOut {
+                game_id: game.game_id,
+                player: game.players[*i],
+                penalty_amount: 0,
+                timestamp: now,
+            });
+        }
+        emit!(GameDrawn {
+            game_id: game.game_id,
+            policy,
+            timestamp: now,
+        });
+        return Ok(());
+    }
+    
+    for i in stalled.iter() {
+        let penalty = forfeit_bond(game, *i, recipient)?;
+        game.set_eliminated(*i);
+        
+        emit!(PlayerTimedOut {
+            game_id: game.game_id,
+            player: game.players[*i],
+            penalty_amount: penalty,
+            timestamp: now,
+        });
+    }
+    
+    let round = game.current_round();
+    if acted.len() == 1 {
+        finish_or_continue_royale(game, round, now);
+    } else if state == GameState::Active {
+        // Everyone left has committed, so move on to the reveal phase
+        let game_type = game.game_type();
+        game.set_type_and_state(game_type, GameState::Resolving);
+        game.phase_deadline = (now + REVEAL_PHASE_TIMEOUT) as u32;
+    } else {
+        resolve_elimination_round(game, now)?;
+    }
+    
+    Ok(())
+}
+
+pub fn claim_draw(ctx: Context<ClaimDraw>) -> Result<()> {
+    let game_key = ctx.accounts.game.key();
+    let mut game = ctx.accounts.game.load_mut()?;
+    let config = &ctx.accounts.config;
+    let clock = Clock::get()?;
+    
+    // Game must have ended in a draw
+    require!(
+        game.game_state() == GameState::Completed,
+        TreasuryError::GameNotCompleted
+    );
+    require!(
+        game.get_flag(FLAG_IS_DRAW),
+        GameError::InvalidGameState
+    );
+    
+    let player_key = ctx.accounts.player.key();
+    let player_index = game.players[..game.player_count as usize]
+        .iter()
+        .position(|p| p == &player_key)
+        .ok_or(TreasuryError::PlayerNotInGame)?;
+    require!(
+        !game.is_eliminated(player_index),
+        GameError::PlayerEliminated
+    );
+    
+    // A cleared stake marks the share as claimed
+    require!(
+        game.stakes[player_index] > 0,
+        TreasuryError::NoStakeToRefund
+    );
+    
+    // The platform fee for the whole game and any forfeited bonds owed to
+    // the treasury go out with the first claim
+    let pot_fee = calculate_platform_fee(config, config.fee_bps(game.game_type()), game.pot_total, 0);
+    if !game.get_flag(FLAG_FEES_DISTRIBUTED) {
+        let platform_fee = draw_platform_fee(&game, pot_fee)
+            .checked_add(game.penalty_amount)
+            .ok_or(GameError::ArithmeticOverflow)?;
+        if platform_fee > 0 {
+            transfer_from_vault(
+                &game_key,
+                game.vault_bump,
+                &ctx.accounts.vault,
+                &ctx.accounts.treasury,
+                &ctx.accounts.system_program.to_account_info(),
+                platform_fee,
+            )?;
+            
+            emit!(FeesCollected {
+                game_id: game.game_id,
+                treasury: ctx.accounts.treasury.key(),
+                amount: platform_fee,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+        game.platform_fee_collected = platform_fee;
+        game.set_flag(FLAG_FEES_DISTRIBUTED, true);
+    }
+    
+    let amount = draw_share(&game, player_index, pot_fee);
+    game.stakes[player_index] = 0;
+    
+    transfer_from_vault(
+        &game_key,
+        game.vault_bump,
+        &ctx.accounts.vault,
+        &ctx.accounts.player.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        amount,
+    )?;
+    
+    match game.draw_policy() {
+        DrawPolicy::SplitPot => emit!(PotSplit {
+            game_id: game.game_id,
+            player: player_key,
+            amount,
+            timestamp: clock.unix_timestamp,
+        }),
+        _ => emit!(DrawRefunded {
+            game_id: game.game_id,
+            player: player_key,
+            amount,
+            timestamp: clock.unix_timestamp,
+        }),
+    }
+    
     Ok(())
 }
 
+// Amount owed to one player of a drawn duel after their part of the pot's
+// platform fee
+fn draw_share(game: &GameAccountOptimized, player_index: usize, pot_fee: u64) -> u64 {
+    match game.draw_policy() {
+        DrawPolicy::SplitPot => (game.pot_total - pot_fee) / game.survivor_count() as u64,
+        _ => {
+            let stake = game.stakes[player_index];
+            stake - stake_fee(game, stake, pot_fee)
+        }
+    }
+}
+
+// Total platform fee for a drawn duel; rounding dust goes to the treasury
+fn draw_platform_fee(game: &GameAccountOptimized, pot_fee: u64) -> u64 {
+    match game.draw_policy() {
+        DrawPolicy::SplitPot => {
+            let share = draw_share(game, 0, pot_fee);
+            game.pot_total - share * game.survivor_count() as u64
+        }
+        _ => game.stakes[..game.player_count as usize]
+            .iter()
+            .map(|stake| stake_fee(game, *stake, pot_fee))
+            .sum(),
+    }
+}
+
+// A refunded stake carries its pro-rata part of the pot's platform fee
+fn stake_fee(game: &GameAccountOptimized, stake: u64, pot_fee: u64) -> u64 {
+    if game.pot_total == 0 {
+        return 0;
+    }
+    (stake as u128 * pot_fee as u128 / game.pot_total as u128) as u64
+}
+
+// Clear commitments, reveals and shared randomness and reopen the commit phase
+fn start_next_round(game: &mut GameAccountOptimized, round: u8, now: i64) {
+    for i in 0..game.player_count as usize {
+        game.commit_hashes[i] = [0; 32];
+        game.reveals_packed[i] = 0;
+    }
+    game.vrf_result = [0; 32];
+    
+    let game_type = game.game_type();
+    game.set_type_and_state(game_type, GameState::Active);
+    let max_rounds = game.max_rounds();
+    game.set_rounds(round, max_rounds);
+    game.phase_deadline = (now + COMMIT_PHASE_TIMEOUT) as u32;
+}
+
+// Helper function to pack move into a byte; numbers are offset by 6 and
+// capped so they never reach the lizard and Spock bytes at the top
+fn pack_move(game_move: GameMove) -> Result<u8> {
+    let byte = match game_move {
+        GameMove::None => 0,
+        GameMove::Rock => 1,
+        GameMove::Paper => 2,
+        GameMove::Scissors => 3,
+        GameMove::Heads => 4,
+        GameMove::Tails => 5,
+        GameMove::Number(n) => {
+            require!(n <= MAX_MOVE_NUMBER, GameError::InvalidMove);
+            6 + n
+        }
+        GameMove::Lizard => 254,
+        GameMove::Spock => 255,
+    };
+    
+    Ok(byte)
+}
+
 // Helper function to unpack move from byte
 fn unpack_move(byte: u8) -> GameMove {
     match byte {
@@ -156,12 +565,47 @@ fn unpack_move(byte: u8) -> GameMove {
         3 => GameMove::Scissors,
         4 => GameMove::Heads,
         5 => GameMove::Tails,
-        n if n >= 6 => GameMove::Number(n - 6),
-        _ => GameMove::None,
+        254 => GameMove::Lizard,
+        255 => GameMove::Spock,
+        n => GameMove::Number(n - 6),
     }
 }
 
-fn determine_winner(game: &mut GameAccountOptimized) -> Result<()> {
+// Check that a revealed move is playable in the game's variant
+fn validate_move(game: &GameAccountOptimized, player_index: usize, game_move: GameMove) -> Result<()> {
+    let valid = match game.variant() {
+        GameVariant::RockPaperScissors | GameVariant::BattleRoyale { .. } => matches!(
+            game_move,
+            GameMove::Rock | GameMove::Paper | GameMove::Scissors
+        ),
+        GameVariant::RockPaperScissorsLizardSpock => matches!(
+            game_move,
+            GameMove::Rock | GameMove::Paper | GameMove::Scissors |
+            GameMove::Lizard | GameMove::Spock
+        ),
+        // Creator reveals their call, the opponent reveals the other side
+        GameVariant::CoinFlip { creator_call } => {
+            let side = if player_index == 0 {
+                creator_call
+            } else if creator_call == GameMove::Heads {
+                GameMove::Tails
+            } else {
+                GameMove::Heads
+            };
+            game_move == side
+        }
+        // The number is committed like any other move but is not scored;
+        // the roll itself comes from the combined nonces
+        GameVariant::DiceBattle { .. } => matches!(game_move, GameMove::Number(_)),
+        GameVariant::Holdem => false,
+    };
+    require!(valid, GameError::InvalidMove);
+    
+    Ok(())
+}
+
+// Score the current round, returning the index of the round winner
+fn determine_winner(game: &GameAccountOptimized) -> Result<Option<usize>> {
     if game.player_count != 2 {
         return Err(GameError::InvalidGameState.into());
     }
@@ -170,24 +614,163 @@ fn determine_winner(game: &mut GameAccountOptimized) -> Result<()> {
     let move1 = unpack_move(game.reveals_packed[0]);
     let move2 = unpack_move(game.reveals_packed[1]);
     
-    let winner_index = match (move1, move2) {
+    let winner_index = match game.variant() {
+        GameVariant::RockPaperScissors |
+        GameVariant::RockPaperScissorsLizardSpock |
+        GameVariant::BattleRoyale { .. } => {
+            if beats(game, move1, move2) {
+                Some(0)
+            } else if beats(game, move2, move1) {
+                Some(1)
+            } else {
+                None // Draw
+            }
+        }
+        GameVariant::CoinFlip { creator_call } => {
+            let outcome = coin_flip_outcome(game);
+            let idx = if outcome == creator_call { 0 } else { 1 };
+            
+            emit!(CoinFlipped {
+                game_id: game.game_id,
+                outcome,
+                winner: game.players[idx],
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+            
+            Some(idx)
+        }
+        GameVariant::DiceBattle { dice_count, sides, tie_break } => {
+            dice_battle_winner(game, dice_count, sides, tie_break)?
+        }
+        GameVariant::Holdem => return Err(GameError::InvalidVariant.into()),
+    };
+    
+    Ok(winner_index)
+}
+
+// Whether one throw beats another under the game's ruleset
+fn beats(game: &GameAccountOptimized, attacker: GameMove, defender: GameMove) -> bool {
+    let classic = matches!(
+        (attacker, defender),
         (GameMove::Rock, GameMove::Scissors) |
         (GameMove::Paper, GameMove::Rock) |
-        (GameMove::Scissors, GameMove::Paper) => Some(0),
+        (GameMove::Scissors, GameMove::Paper)
+    );
+    if game.variant() != GameVariant::RockPaperScissorsLizardSpock {
+        return classic;
+    }
+    
+    // Each throw beats exactly two of the other four
+    classic || matches!(
+        (attacker, defender),
+        (GameMove::Rock, GameMove::Lizard) |
+        (GameMove::Lizard, GameMove::Spock) |
+        (GameMove::Spock, GameMove::Scissors) |
+        (GameMove::Scissors, GameMove::Lizard) |
+        (GameMove::Lizard, GameMove::Paper) |
+        (GameMove::Paper, GameMove::Spock) |
+        (GameMove::Spock, GameMove::Rock)
+    )
+}
+
+// Flip the coin from both revealed nonces; neither player knows the
+// other's nonce when committing, so neither can bias the result
+fn coin_flip_outcome(game: &GameAccountOptimized) -> GameMove {
+    let seed = hash(&game.vrf_result).to_bytes();
+    if seed[0] & 1 == 0 {
+        GameMove::Heads
+    } else {
+        GameMove::Tails
+    }
+}
+
+// Roll one player's dice; the seed mixes both nonces with the player
+// index and attempt number so each player and each reroll is independent
+fn roll_dice(
+    game: &GameAccountOptimized,
+    player_index: usize,
+    attempt: u8,
+    dice_count: u8,
+    sides: u8,
+) -> [u8; MAX_DICE as usize] {
+    let mut dice = [0u8; MAX_DICE as usize];
+    for (die, value) in dice.iter_mut().enumerate().take(dice_count as usize) {
+        let seed = hashv(&[
+            &game.vrf_result,
+            &[player_index as u8, attempt, die as u8],
+        ]).to_bytes();
+        let roll = u64::from_le_bytes(seed[..8].try_into().unwrap());
+        *value = (roll % sides as u64) as u8 + 1;
+    }
+    dice
+}
+
+// Roll both players' dice, rerolling ties while the tie-break allows. Returns
+// the final rolls and totals, the winner if there is one, and the number of
+// attempts made.
+fn roll_off(
+    game: &GameAccountOptimized,
+    dice_count: u8,
+    sides: u8,
+    tie_break: DiceTieBreak,
+) -> ([[u8; MAX_DICE as usize]; 2], [u16; 2], Option<usize>, u8) {
+    let max_attempts = if tie_break == DiceTieBreak::Reroll { DICE_MAX_REROLLS + 1 } else { 1 };
+    
+    let mut attempt = 0;
+    loop {
+        let rolls = [
+            roll_dice(game, 0, attempt, dice_count, sides),
+            roll_dice(game, 1, attempt, dice_count, sides),
+        ];
+        let totals = [
+            rolls[0].iter().map(|d| *d as u16).sum::<u16>(),
+            rolls[1].iter().map(|d| *d as u16).sum::<u16>(),
+        ];
         
-        (GameMove::Scissors, GameMove::Rock) |
-        (GameMove::Rock, GameMove::Paper) |
-        (GameMove::Paper, GameMove::Scissors) => Some(1),
+        let winner_index = if totals[0] != totals[1] {
+            Some(if totals[0] > totals[1] { 0 } else { 1 })
+        } else if tie_break == DiceTieBreak::HighestDie {
+            // Compare dice from highest to lowest
+            let mut sorted = rolls;
+            sorted[0].sort_unstable_by(|a, b| b.cmp(a));
+            sorted[1].sort_unstable_by(|a, b| b.cmp(a));
+            match sorted[0].cmp(&sorted[1]) {
+                std::cmp::Ordering::Greater => Some(0),
+                std::cmp::Ordering::Less => Some(1),
+                std::cmp::Ordering::Equal => None,
+            }
+        } else {
+            None
+        };
         
-        _ => None, // Draw
-    };
+        attempt += 1;
+        if winner_index.is_some() || attempt >= max_attempts {
+            break (rolls, totals, winner_index, attempt);
+        }
+    }
+}
+
+fn dice_battle_winner(
+    game: &GameAccountOptimized,
+    dice_count: u8,
+    sides: u8,
+    tie_break: DiceTieBreak,
+) -> Result<Option<usize>> {
+    let (rolls, totals, winner_index, attempt) = roll_off(game, dice_count, sides, tie_break);
     
-    if let Some(idx) = winner_index {
-        game.winner = game.players[idx];
-        game.has_winner = 1;
+    let timestamp = Clock::get()?.unix_timestamp;
+    for i in 0..2 {
+        emit!(DiceRolled {
+            game_id: game.game_id,
+            player: game.players[i],
+            dice: rolls[i][..dice_count as usize].to_vec(),
+            total: totals[i],
+            rerolls: attempt - 1,
+            timestamp,
+        });
     }
     
-    Ok(())
+    Ok(winner_index)
 }
 
 #[derive(Accounts)]
@@ -204,4 +787,86 @@ pub struct RevealMove<'info> {
     pub game: AccountLoader<'info, GameAccountOptimized>,
     
     pub player: Signer<'info>,
-}
\ No newline at end of file
+}
+
+#[derive(Accounts)]
+pub struct ClaimDraw<'info> {
+    #[account(mut)]
+    pub game: AccountLoader<'info, GameAccountOptimized>,
+    
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, ConfigurationAccount>,
+    
+    /// CHECK: Vault PDA holding this game's stakes
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump = game.load()?.vault_bump
+    )]
+    pub vault: AccountInfo<'info>,
+    
+    /// CHECK: Treasury account for platform fees, as named in the config
+    #[account(mut, address = config.treasury @ TreasuryError::InvalidTreasury)]
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::Zeroable;
+    
+    // First duel, by seed, whose opening roll of two six-sided dice is a tie
+    // on totals that the highest die still separates
+    fn tied_duel() -> Box<GameAccountOptimized> {
+        let mut game = Box::new(GameAccountOptimized::zeroed());
+        for n in 0u32.. {
+            game.vrf_result = hash(&n.to_le_bytes()).to_bytes();
+            let (rolls, totals, _, _) = roll_off(&game, 2, 6, DiceTieBreak::Draw);
+            if totals[0] == totals[1] && rolls[0][..2].iter().max() != rolls[1][..2].iter().max() {
+                return game;
+            }
+        }
+        unreachable!()
+    }
+    
+    #[test]
+    fn equal_totals_draw_without_a_tie_break() {
+        let game = tied_duel();
+        let (_, totals, winner, attempts) = roll_off(&game, 2, 6, DiceTieBreak::Draw);
+        assert_eq!(totals[0], totals[1]);
+        assert_eq!(winner, None);
+        assert_eq!(attempts, 1);
+    }
+    
+    #[test]
+    fn highest_die_breaks_equal_totals() {
+        let game = tied_duel();
+        let (rolls, _, winner, attempts) = roll_off(&game, 2, 6, DiceTieBreak::HighestDie);
+        let highest = |i: usize| *rolls[i][..2].iter().max().unwrap();
+        let expected = if highest(0) > highest(1) { 0 } else { 1 };
+        assert_eq!(winner, Some(expected));
+        assert_eq!(attempts, 1);
+    }
+    
+    #[test]
+    fn reroll_plays_on_until_the_totals_differ() {
+        let game = tied_duel();
+        let (_, totals, winner, attempts) = roll_off(&game, 2, 6, DiceTieBreak::Reroll);
+        assert!(attempts > 1);
+        assert_ne!(totals[0], totals[1]);
+        assert_eq!(winner, Some(if totals[0] > totals[1] { 0 } else { 1 }));
+    }
+    
+    #[test]
+    fn reroll_gives_up_as_a_draw_after_the_last_attempt() {
+        // Single-sided dice always tie
+        let game = Box::new(GameAccountOptimized::zeroed());
+        let (_, _, winner, attempts) = roll_off(&game, 3, 1, DiceTieBreak::Reroll);
+        assert_eq!(winner, None);
+        assert_eq!(attempts, DICE_MAX_REROLLS + 1);
+    }
+}
@@ -1,8 +1,12 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use crate::state::{ConfigurationAccount, GameAccountOptimized};
-use crate::events::FeesCollected;
-use crate::errors::TreasuryError;
+use crate::state::{
+    ConfigurationAccount, GameAccountOptimized, GameType, GameState, FoldPenaltyRecipient,
+    FLAG_FEES_DISTRIBUTED, FLAG_MUTUAL_CANCEL,
+};
+use crate::constants::{BPS_DENOMINATOR, CONFIG_SEED, VAULT_SEED};
+use crate::events::{BondReturned, FeesCollected, StakeRefunded};
+use crate::errors::{GameError, TreasuryError};
 
 /// Treasury fee collection and distribution logic
 pub fn collect_platform_fee<'info>(
@@ -17,10 +21,15 @@ pub fn collect_platform_fee<'info>(
         !game.get_flag(crate::state::game_optimized::FLAG_FEES_DISTRIBUTED),
         TreasuryError::FeesAlreadyDistributed
     );
+    require_treasury(treasury, config)?;
     
     // Calculate platform fee
-    let platform_fee_percentage = config.platform_fee as u64;
-    let platform_fee_amount = (game.pot_total * platform_fee_percentage) / 100;
+    let platform_fee_amount = calculate_platform_fee(
+        config,
+        config.fee_bps(game.game_type()),
+        game.pot_total,
+        0,
+    );
     
     // Store fee amount in game account
     game.platform_fee_collected = platform_fee_amount;
@@ -53,12 +62,42 @@ pub fn collect_platform_fee<'info>(
     Ok(())
 }
 
+/// Platform fee owed on `amount` at a rate of `fee_bps` basis points.
+///
+/// The rate is a game type's payout fee or the cancel fee, and the result is
+/// capped so the platform fees taken from one game never exceed
+/// `config.max_rake`. `fees_taken` is the platform fee already charged to the
+/// game by earlier payouts or refunds.
+pub fn calculate_platform_fee(
+    config: &ConfigurationAccount,
+    fee_bps: u16,
+    amount: u64,
+    fees_taken: u64,
+) -> u64 {
+    let fee = (amount as u128 * fee_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    if config.max_rake == 0 {
+        return fee;
+    }
+    fee.min(config.max_rake.saturating_sub(fees_taken))
+}
+
+/// Platform fee a game has already paid toward the rake cap.
+///
+/// Forfeited bonds paid to the treasury are recorded with the fees once
+/// distributed, but they are not rake.
+pub(crate) fn rake_taken(game: &GameAccountOptimized) -> u64 {
+    let penalty_paid = game.get_flag(FLAG_FEES_DISTRIBUTED);
+    game.platform_fee_collected
+        .saturating_sub(if penalty_paid { game.penalty_amount } else { 0 })
+}
+
 /// Calculate winner payout after fees
 pub fn calculate_winner_payout(
+    config: &ConfigurationAccount,
+    game_type: GameType,
     pot_total: u64,
-    platform_fee_percentage: u8,
 ) -> (u64, u64) {
-    let platform_fee = (pot_total * platform_fee_percentage as u64) / 100;
+    let platform_fee = calculate_platform_fee(config, config.fee_bps(game_type), pot_total, 0);
     let winner_payout = pot_total - platform_fee;
     
     (winner_payout, platform_fee)
@@ -90,11 +129,13 @@ pub fn distribute_winnings_with_fees<'info>(
         game.winner == winner.key(),
         TreasuryError::InvalidWinner
     );
+    require_treasury(treasury, config)?;
     
     // Calculate payouts
     let (winner_payout, platform_fee) = calculate_winner_payout(
+        config,
+        game.game_type(),
         game.pot_total,
-        config.platform_fee,
     );
     
     // Transfer platform fee to treasury
@@ -138,116 +179,458 @@ pub fn distribute_winnings_with_fees<'info>(
     Ok(())
 }
 
-/// Refund players with fee deduction for cancelled games
+/// Fees may only be paid to the treasury named in the config
+pub fn require_treasury(treasury: &AccountInfo, config: &ConfigurationAccount) -> Result<()> {
+    require_keys_eq!(
+        treasury.key(),
+        config.treasury,
+        TreasuryError::InvalidTreasury
+    );
+    Ok(())
+}
+
+/// Move lamports out of a game's vault, signing for the vault PDA
+pub(crate) fn transfer_from_vault<'info>(
+    game_key: &Pubkey,
+    vault_bump: u8,
+    vault: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, game_key.as_ref(), &[vault_bump]]];
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Transfer {
+                from: vault.clone(),
+                to: to.clone(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+/// Lamports still needed to keep a game's vault rent exempt.
+///
+/// Whoever opens a game tops the vault up to this reserve, so payouts can
+/// always take every stake back out of it.
+pub(crate) fn vault_rent_reserve(vault: &AccountInfo) -> Result<u64> {
+    Ok(Rent::get()?.minimum_balance(0).saturating_sub(vault.lamports()))
+}
+
+/// Penalty bond each player of a new game posts on top of `stake`.
+///
+/// Duels and tables charge forfeits against the bond, so the offender pays
+/// the penalty rather than the players who stayed in. Lotteries have no
+/// forfeits and post none.
+pub(crate) fn penalty_bond(
+    config: &ConfigurationAccount,
+    game_type: GameType,
+    stake: u64,
+) -> Result<u64> {
+    if game_type == GameType::Lottery {
+        return Ok(0);
+    }
+    Ok(stake
+        .checked_mul(config.fold_penalty as u64)
+        .ok_or(GameError::ArithmeticOverflow)?
+        / 100)
+}
+
+/// Forfeit a seat's penalty bond after a fold or timeout.
+///
+/// The bond goes to the treasury with the first claim, or into the pot as
+/// part of the seat's stake for the other players to win. Returns the amount
+/// charged, which is zero if the bond was already forfeited or returned.
+pub(crate) fn forfeit_bond(
+    game: &mut GameAccountOptimized,
+    seat: usize,
+    recipient: FoldPenaltyRecipient,
+) -> Result<u64> {
+    if !bond_posted(game, seat) {
+        return Ok(0);
+    }
+    game.bond_mask |= 1 << seat;
+    
+    let bond = game.penalty_bond;
+    match recipient {
+        FoldPenaltyRecipient::Treasury => {
+            game.penalty_amount = game.penalty_amount
+                .checked_add(bond)
+                .ok_or(GameError::ArithmeticOverflow)?;
+        }
+        FoldPenaltyRecipient::Opponent => {
+            game.stakes[seat] = game.stakes[seat]
+                .checked_add(bond)
+                .ok_or(GameError::ArithmeticOverflow)?;
+            game.pot_total = game.pot_total
+                .checked_add(bond)
+                .ok_or(GameError::ArithmeticOverflow)?;
+        }
+    }
+    
+    Ok(bond)
+}
+
+// Mark a seat's bond as returned and give the amount still owed to it
+fn release_bond(game: &mut GameAccountOptimized, seat: usize) -> u64 {
+    if !bond_posted(game, seat) {
+        return 0;
+    }
+    game.bond_mask |= 1 << seat;
+    game.penalty_bond
+}
+
+// Whether a seat's bond is still held by the game. Only duel and table seats
+// post one, and those all fit in the 16-bit mask; lottery entries go past it.
+fn bond_posted(game: &GameAccountOptimized, seat: usize) -> bool {
+    game.penalty_bond > 0 && seat < 16 && game.bond_mask & (1 << seat) == 0
+}
+
+/// Refund a player's stake and bond from a cancelled game, less any cancel
+/// fee that goes to the treasury. Returns the amount refunded.
+#[allow(clippy::too_many_arguments)]
 pub fn refund_with_penalty<'info>(
     game: &mut GameAccountOptimized,
-    game_account_info: &AccountInfo<'info>,
-    config: &Account<'info, ConfigurationAccount>,
+    game_key: &Pubkey,
+    config: &ConfigurationAccount,
+    vault: &AccountInfo<'info>,
     player: &AccountInfo<'info>,
     treasury: &AccountInfo<'info>,
-    system_program: &Program<'info, System>,
-    apply_penalty: bool,
-) -> Result<()> {
-    // Find player index
-    let player_index = game.players
-        .iter()
-        .take(game.player_count as usize)
-        .position(|p| p == &player.key())
-        .ok_or(TreasuryError::PlayerNotInGame)?;
+    system_program: &AccountInfo<'info>,
+    player_index: usize,
+) -> Result<u64> {
+    let penalty_bps = refund_penalty(game, config)?;
+    require_treasury(treasury, config)?;
     
     // Get player's stake
     let stake = game.stakes[player_index];
     require!(stake > 0, TreasuryError::NoStakeToRefund);
     
-    let refund_amount = if apply_penalty {
-        // Apply cancellation penalty
-        let penalty_percentage = config.fold_penalty as u64;
-        let penalty_amount = (stake * penalty_percentage) / 100;
-        
-        // Transfer penalty to treasury
-        if penalty_amount > 0 {
-            let penalty_transfer = CpiContext::new(
-                system_program.to_account_info(),
-                system_program::Transfer {
-                    from: game_account_info.clone(),
-                    to: treasury.clone(),
-                },
-            );
-            
-            system_program::transfer(penalty_transfer, penalty_amount)?;
-            
-            emit!(FeesCollected {
-                game_id: game.game_id,
-                treasury: treasury.key(),
-                amount: penalty_amount,
-                timestamp: Clock::get()?.unix_timestamp,
-            });
-        }
-        
-        stake - penalty_amount
-    } else {
-        stake
-    };
+    let penalty_amount = calculate_platform_fee(config, penalty_bps, stake, rake_taken(game));
+    let refund_amount = stake - penalty_amount + release_bond(game, player_index);
+    let timestamp = Clock::get()?.unix_timestamp;
     
-    // Transfer refund to player
-    if refund_amount > 0 {
-        let refund_transfer = CpiContext::new(
-            system_program.to_account_info(),
-            system_program::Transfer {
-                from: game_account_info.clone(),
-                to: player.clone(),
-            },
-        );
+    // Transfer penalty to treasury, along with any forfeited bonds owed to
+    // it, which go out with the first refund
+    let forfeited = if game.get_flag(FLAG_FEES_DISTRIBUTED) { 0 } else { game.penalty_amount };
+    let treasury_amount = penalty_amount
+        .checked_add(forfeited)
+        .ok_or(GameError::ArithmeticOverflow)?;
+    game.set_flag(FLAG_FEES_DISTRIBUTED, true);
+    if treasury_amount > 0 {
+        transfer_from_vault(game_key, game.vault_bump, vault, treasury, system_program, treasury_amount)?;
+        game.platform_fee_collected = game.platform_fee_collected
+            .checked_add(treasury_amount)
+            .ok_or(GameError::ArithmeticOverflow)?;
         
-        system_program::transfer(refund_transfer, refund_amount)?;
+        emit!(FeesCollected {
+            game_id: game.game_id,
+            treasury: treasury.key(),
+            amount: treasury_amount,
+            timestamp,
+        });
     }
     
+    // Transfer refund to player
+    transfer_from_vault(game_key, game.vault_bump, vault, player, system_program, refund_amount)?;
+    
     // Clear player's stake
     game.stakes[player_index] = 0;
+    game.pot_total -= stake;
+    
+    emit!(StakeRefunded {
+        game_id: game.game_id,
+        player: player.key(),
+        amount: refund_amount,
+        penalty_amount,
+        timestamp,
+    });
+    
+    Ok(refund_amount)
+}
+
+/// Reclaim the caller's stake from a cancelled game
+pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+    let game_key = ctx.accounts.game.key();
+    let mut game = ctx.accounts.game.load_mut()?;
+    
+    // Lottery entries share a player, so take the next seat still holding a stake
+    let player_key = ctx.accounts.player.key();
+    let player_index = (0..game.player_count as usize)
+        .find(|i| game.players[*i] == player_key && game.stakes[*i] > 0)
+        .ok_or(TreasuryError::NoStakeToRefund)?;
+    require!(
+        !game.is_eliminated(player_index),
+        GameError::PlayerEliminated
+    );
+    
+    refund_with_penalty(
+        &mut game,
+        &game_key,
+        &ctx.accounts.config,
+        &ctx.accounts.vault,
+        &ctx.accounts.player.to_account_info(),
+        &ctx.accounts.treasury,
+        &ctx.accounts.system_program.to_account_info(),
+        player_index,
+    )?;
     
     Ok(())
 }
 
-/// Batch refund all players (for cancelled games)
+/// Take back the caller's penalty bond once a game is over.
+///
+/// Bonds that were not forfeited are returned to every player, winner or
+/// not; refunds from a cancelled game include the bond already.
+pub fn claim_bond(ctx: Context<ClaimBond>) -> Result<()> {
+    let game_key = ctx.accounts.game.key();
+    let mut game = ctx.accounts.game.load_mut()?;
+    
+    let state = game.game_state();
+    require!(
+        state == GameState::Completed || state == GameState::Cancelled,
+        TreasuryError::GameNotCompleted
+    );
+    
+    let player_key = ctx.accounts.player.key();
+    let player_index = game.players[..game.player_count as usize]
+        .iter()
+        .position(|p| p == &player_key)
+        .ok_or(TreasuryError::PlayerNotInGame)?;
+    let amount = release_bond(&mut game, player_index);
+    require!(amount > 0, TreasuryError::NoBondToReturn);
+    
+    transfer_from_vault(
+        &game_key,
+        game.vault_bump,
+        &ctx.accounts.vault,
+        &ctx.accounts.player.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        amount,
+    )?;
+    
+    emit!(BondReturned {
+        game_id: game.game_id,
+        player: player_key,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Batch refund players of a cancelled game, starting at seat `start`.
+///
+/// `remaining_accounts` holds one writable account per seat from `start`
+/// on, each matching the player in that seat. Seats already refunded or
+/// knocked out are skipped, so anyone can page through a large game.
 pub fn batch_refund_all_players<'info>(
-    ctx: Context<BatchRefund<'info>>,
+    ctx: Context<'_, '_, '_, 'info, BatchRefund<'info>>,
+    start: u8,
 ) -> Result<()> {
+    let game_key = ctx.accounts.game.key();
     let mut game = ctx.accounts.game.load_mut()?;
     
-    // Ensure game is cancelled
+    let start = start as usize;
     require!(
-        game.game_state() == crate::state::GameState::Cancelled,
-        TreasuryError::GameNotCancelled
+        start + ctx.remaining_accounts.len() <= game.player_count as usize,
+        TreasuryError::PlayerNotInGame
     );
     
-    // Refund each player
-    for i in 0..game.player_count as usize {
-        if game.stakes[i] > 0 {
-            // Transfer full stake back (no penalty for cancellation)
-            let refund_amount = game.stakes[i];
-            
-            // Direct lamports transfer from game PDA
-            **ctx.accounts.game.to_account_info().try_borrow_mut_lamports()? -= refund_amount;
-            **ctx.remaining_accounts[i].try_borrow_mut_lamports()? += refund_amount;
-            
-            // Clear stake
-            game.stakes[i] = 0;
+    for (offset, player) in ctx.remaining_accounts.iter().enumerate() {
+        let i = start + offset;
+        require_keys_eq!(
+            player.key(),
+            game.players[i],
+            TreasuryError::PlayerNotInGame
+        );
+        if game.stakes[i] == 0 || game.is_eliminated(i) {
+            continue;
         }
+        
+        refund_with_penalty(
+            &mut game,
+            &game_key,
+            &ctx.accounts.config,
+            &ctx.accounts.vault,
+            player,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program.to_account_info(),
+            i,
+        )?;
     }
     
-    // Mark as refunded
-    game.pot_total = 0;
-    
     Ok(())
 }
 
+// Cancelled games refund in full, except agreed cancellations which keep the
+// configured cancel fee
+fn refund_penalty(game: &GameAccountOptimized, config: &ConfigurationAccount) -> Result<u16> {
+    require!(
+        game.game_state() == GameState::Cancelled,
+        TreasuryError::GameNotCancelled
+    );
+    
+    Ok(if game.get_flag(FLAG_MUTUAL_CANCEL) { config.cancel_fee_bps() } else { 0 })
+}
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    #[account(mut)]
+    pub game: AccountLoader<'info, GameAccountOptimized>,
+    
+    #[account(seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, ConfigurationAccount>,
+    
+    /// CHECK: Vault PDA holding this game's stakes
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump = game.load()?.vault_bump
+    )]
+    pub vault: AccountInfo<'info>,
+    
+    /// CHECK: Treasury account for cancel fees, as named in the config
+    #[account(mut, address = config.treasury @ TreasuryError::InvalidTreasury)]
+    pub treasury: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimBond<'info> {
+    #[account(mut)]
+    pub game: AccountLoader<'info, GameAccountOptimized>,
+    
+    /// CHECK: Vault PDA holding this game's stakes
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump = game.load()?.vault_bump
+    )]
+    pub vault: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct BatchRefund<'info> {
     #[account(mut)]
     pub game: AccountLoader<'info, GameAccountOptimized>,
+    
+    #[account(seeds = [CONFIG_SEED], bump)]
     pub config: Account<'info, ConfigurationAccount>,
+    
+    /// CHECK: Vault PDA holding this game's stakes
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump = game.load()?.vault_bump
+    )]
+    pub vault: AccountInfo<'info>,
+    
+    /// CHECK: Treasury account for cancel fees, as named in the config
+    #[account(mut, address = config.treasury @ TreasuryError::InvalidTreasury)]
+    pub treasury: AccountInfo<'info>,
+    
+    pub caller: Signer<'info>,
+    
     pub system_program: Program<'info, System>,
-    // remaining_accounts contains all players to refund
+    // remaining_accounts contains the players to refund, in seat order
 }
 
-// TreasuryError is imported from crate::errors
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::Zeroable;
+    
+    fn config(fee_bps: u16, max_rake: u64) -> ConfigurationAccount {
+        ConfigurationAccount {
+            admin: Pubkey::default(),
+            min_stake: 0,
+            max_stake: u64::MAX,
+            max_rounds: 0,
+            fold_penalty: 0,
+            randomness_method: 0,
+            platform_fee: 0,
+            item_prices: Vec::new(),
+            timeout: 0,
+            ticket_conversion: 1,
+            game_counter: 0,
+            treasury: Pubkey::default(),
+            fold_penalty_recipient: FoldPenaltyRecipient::Treasury,
+            cancel_fee: 0,
+            duel_fee_bps: fee_bps,
+            multi_round_fee_bps: fee_bps,
+            lottery_fee_bps: fee_bps,
+            max_rake,
+            config_version: crate::constants::CONFIG_VERSION,
+            cancel_fee_bps: fee_bps,
+            reserved: [0; 109],
+        }
+    }
+    
+    #[test]
+    fn bonds_are_returned_once() {
+        let mut game = Box::new(GameAccountOptimized::zeroed());
+        game.player_count = 2;
+        game.penalty_bond = 50;
+        
+        assert_eq!(release_bond(&mut game, 1), 50);
+        assert_eq!(release_bond(&mut game, 1), 0);
+        assert_eq!(forfeit_bond(&mut game, 1, FoldPenaltyRecipient::Treasury).unwrap(), 0);
+        assert_eq!(release_bond(&mut game, 0), 50);
+    }
+    
+    #[test]
+    fn lottery_seats_past_the_bond_mask_hold_no_bond() {
+        let mut game = Box::new(GameAccountOptimized::zeroed());
+        game.set_type_and_state(GameType::Lottery, GameState::Cancelled);
+        game.player_count = 20;
+        
+        assert_eq!(release_bond(&mut game, 17), 0);
+        assert_eq!(forfeit_bond(&mut game, 19, FoldPenaltyRecipient::Opponent).unwrap(), 0);
+        assert_eq!(game.bond_mask, 0);
+    }
+    
+    #[test]
+    fn platform_fee_is_charged_in_basis_points() {
+        let config = config(250, 0);
+        assert_eq!(calculate_platform_fee(&config, 250, 1_000_000, 0), 25_000);
+        
+        // Fractions of a lamport round down
+        assert_eq!(calculate_platform_fee(&config, 250, 39, 0), 0);
+        assert_eq!(calculate_platform_fee(&config, 0, 1_000_000, 0), 0);
+    }
+    
+    #[test]
+    fn platform_fee_stops_at_the_rake_cap() {
+        let config = config(500, 30_000);
+        
+        // A single payout is capped at the rake cap
+        assert_eq!(calculate_platform_fee(&config, 500, 1_000_000, 0), 30_000);
+        
+        // Later payouts only take what is left under the cap, then nothing
+        assert_eq!(calculate_platform_fee(&config, 500, 400_000, 0), 20_000);
+        assert_eq!(calculate_platform_fee(&config, 500, 400_000, 20_000), 10_000);
+        assert_eq!(calculate_platform_fee(&config, 500, 400_000, 30_000), 0);
+        assert_eq!(calculate_platform_fee(&config, 500, 400_000, 45_000), 0);
+        
+        // No cap without a max rake
+        let uncapped = ConfigurationAccount { max_rake: 0, ..config };
+        assert_eq!(calculate_platform_fee(&uncapped, 500, 1_000_000, 1_000_000), 50_000);
+    }
+}
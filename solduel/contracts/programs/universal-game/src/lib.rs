@@ -1,9 +1,11 @@
 use anchor_lang::prelude::*;
 
+pub mod commitment;
 pub mod constants;
 pub mod errors;
 pub mod events;
 pub mod instructions;
+pub mod poker;
 pub mod state;
 pub mod token;
 pub mod vrf;
@@ -23,29 +25,77 @@ pub mod universal_game {
     }
 
     /// Update configuration parameters (admin only)
+    #[allow(clippy::too_many_arguments)]
     pub fn update_config(
         ctx: Context<UpdateConfig>,
         min_stake: Option<u64>,
         max_stake: Option<u64>,
-        platform_fee: Option<u8>,
+        duel_fee_bps: Option<u16>,
+        multi_round_fee_bps: Option<u16>,
+        lottery_fee_bps: Option<u16>,
+        max_rake: Option<u64>,
         timeout: Option<u64>,
+        fold_penalty: Option<u8>,
+        fold_penalty_recipient: Option<FoldPenaltyRecipient>,
+        cancel_fee_bps: Option<u16>,
     ) -> Result<()> {
-        instructions::admin::update_config(ctx, min_stake, max_stake, platform_fee, timeout)
+        instructions::admin::update_config(
+            ctx,
+            min_stake,
+            max_stake,
+            duel_fee_bps,
+            multi_round_fee_bps,
+            lottery_fee_bps,
+            max_rake,
+            timeout,
+            fold_penalty,
+            fold_penalty_recipient,
+            cancel_fee_bps,
+        )
+    }
+
+    /// Upgrade the configuration to the current layout (admin only)
+    pub fn migrate_config(ctx: Context<UpdateConfig>) -> Result<()> {
+        instructions::admin::migrate_config(ctx)
     }
 
     /// Create a new game of any type
+    #[allow(clippy::too_many_arguments)]
     pub fn create_game(
         ctx: Context<CreateGame>,
         game_type: GameType,
         stake_amount: u64,
         max_players: Option<u8>,
+        variant: Option<GameVariant>,
+        best_of: Option<u8>,
+        draw_policy: Option<DrawPolicy>,
+        hand_commitment: Option<[u8; 32]>,
+        betting_structure: Option<BettingStructure>,
+        action_timeout: Option<u8>,
+        access: Option<GameAccess>,
     ) -> Result<()> {
-        instructions::game_lifecycle::create_game(ctx, game_type, stake_amount, max_players)
+        instructions::game_lifecycle::create_game(
+            ctx,
+            game_type,
+            stake_amount,
+            max_players,
+            variant,
+            best_of,
+            draw_policy,
+            hand_commitment,
+            betting_structure,
+            action_timeout,
+            access,
+        )
     }
 
     /// Join an existing game
-    pub fn join_game(ctx: Context<JoinGame>) -> Result<()> {
-        instructions::game_lifecycle::join_game(ctx)
+    pub fn join_game(
+        ctx: Context<JoinGame>,
+        hand_commitment: Option<[u8; 32]>,
+        access_code: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::game_lifecycle::join_game(ctx, hand_commitment, access_code)
     }
 
     /// Cancel a waiting game (creator only)
@@ -53,6 +103,39 @@ pub mod universal_game {
         instructions::game_lifecycle::cancel_game(ctx)
     }
 
+    /// Offer to cancel a game in play by mutual agreement
+    pub fn offer_cancel(ctx: Context<MutualCancel>) -> Result<()> {
+        instructions::game_lifecycle::offer_cancel(ctx)
+    }
+
+    /// Accept a standing offer to cancel a game in play
+    pub fn accept_cancel(ctx: Context<MutualCancel>) -> Result<()> {
+        instructions::game_lifecycle::accept_cancel(ctx)
+    }
+
+    /// Withdraw your standing offer to cancel
+    pub fn withdraw_cancel(ctx: Context<MutualCancel>) -> Result<()> {
+        instructions::game_lifecycle::withdraw_cancel(ctx)
+    }
+
+    /// Reclaim your stake from a cancelled game
+    pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+        instructions::treasury::claim_refund(ctx)
+    }
+
+    /// Take back your penalty bond once a game is over
+    pub fn claim_bond(ctx: Context<ClaimBond>) -> Result<()> {
+        instructions::treasury::claim_bond(ctx)
+    }
+
+    /// Refund the players of a cancelled game (anyone can call)
+    pub fn batch_refund<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchRefund<'info>>,
+        start: u8,
+    ) -> Result<()> {
+        instructions::treasury::batch_refund_all_players(ctx, start)
+    }
+
     /// Force finish a timed-out game
     pub fn force_finish(ctx: Context<ForceFinish>) -> Result<()> {
         instructions::game_lifecycle::force_finish(ctx)
@@ -72,11 +155,61 @@ pub mod universal_game {
         instructions::simple_duel::reveal_move(ctx, game_move, nonce)
     }
 
+    /// Claim a refund or pot share from a drawn duel
+    pub fn claim_draw(ctx: Context<ClaimDraw>) -> Result<()> {
+        instructions::simple_duel::claim_draw(ctx)
+    }
+
     /// Place a bet in multi-round games
     pub fn place_bet(ctx: Context<PlaceBet>, action: BetAction) -> Result<()> {
         instructions::multi_round::place_bet(ctx, action)
     }
 
+    /// Check or fold for a MultiRound player whose action clock ran out (anyone can call)
+    pub fn enforce_action_timeout(ctx: Context<EnforceActionTimeout>) -> Result<()> {
+        instructions::multi_round::enforce_action_timeout(ctx)
+    }
+
+    /// Reveal a MultiRound hand secret at showdown
+    pub fn reveal_hand(ctx: Context<RevealHand>, secret: [u8; 32]) -> Result<()> {
+        instructions::multi_round::reveal_hand(ctx, secret)
+    }
+
+    /// Open the Hold'em deck entries for the next street
+    pub fn reveal_board_cards(
+        ctx: Context<RevealBoardCards>,
+        openings: Vec<CardOpening>,
+    ) -> Result<()> {
+        instructions::holdem::reveal_board_cards(ctx, openings)
+    }
+
+    /// Reveal a Hold'em seat's whole deck permutation at showdown
+    pub fn reveal_deck(
+        ctx: Context<RevealDeck>,
+        permutation: [u8; constants::DECK_CARDS],
+        salt_seed: [u8; 32],
+    ) -> Result<()> {
+        instructions::holdem::reveal_deck(ctx, permutation, salt_seed)
+    }
+
+    /// Propose a rematch of a completed game, creating the new game
+    pub fn propose_rematch(
+        ctx: Context<ProposeRematch>,
+        hand_commitment: Option<[u8; 32]>,
+        roll_in: bool,
+    ) -> Result<()> {
+        instructions::rematch::propose_rematch(ctx, hand_commitment, roll_in)
+    }
+
+    /// Accept a rematch by taking a seat in the new game
+    pub fn accept_rematch(
+        ctx: Context<AcceptRematch>,
+        hand_commitment: Option<[u8; 32]>,
+        roll_in: bool,
+    ) -> Result<()> {
+        instructions::rematch::accept_rematch(ctx, hand_commitment, roll_in)
+    }
+
     /// Enter the lottery pool
     pub fn enter_lottery(ctx: Context<EnterLottery>, num_tickets: u32) -> Result<()> {
         instructions::lottery::enter_lottery(ctx, num_tickets)
@@ -92,7 +225,7 @@ pub mod universal_game {
         instructions::claim_winnings(ctx)
     }
 
-    /// Resolve a completed game
+    /// Settle a showdown whose reveal deadline has passed
     pub fn resolve_game(ctx: Context<ResolveGame>) -> Result<()> {
         instructions::resolve_game(ctx)
     }
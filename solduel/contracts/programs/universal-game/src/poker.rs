@@ -0,0 +1,247 @@
+use anchor_lang::solana_program::hash::hashv;
+use std::cmp::Reverse;
+use crate::constants::DECK_CARDS;
+
+/// Cards in a standard deck; card `c` has rank `c % 13` (0 = two, 12 = ace)
+/// and suit `c / 13`
+pub const DECK_SIZE: u8 = DECK_CARDS as u8;
+
+/// Cards dealt to each player at a MultiRound showdown
+pub const HAND_SIZE: usize = 5;
+
+/// Hand categories, weakest first
+pub const HIGH_CARD: u8 = 0;
+pub const ONE_PAIR: u8 = 1;
+pub const TWO_PAIR: u8 = 2;
+pub const THREE_OF_A_KIND: u8 = 3;
+pub const STRAIGHT: u8 = 4;
+pub const FLUSH: u8 = 5;
+pub const FULL_HOUSE: u8 = 6;
+pub const FOUR_OF_A_KIND: u8 = 7;
+pub const STRAIGHT_FLUSH: u8 = 8;
+
+/// Deal one hand per player from a single shuffled deck.
+///
+/// The shuffle is a partial Fisher-Yates driven by `seed`, so no card is
+/// dealt twice and every player's hand depends on every revealed secret.
+pub fn deal_hands(seed: &[u8; 32], players: usize) -> Vec<[u8; HAND_SIZE]> {
+    let mut deck: Vec<u8> = (0..DECK_SIZE).collect();
+    let mut hands = vec![[0u8; HAND_SIZE]; players];
+    
+    for k in 0..players * HAND_SIZE {
+        let draw = hashv(&[seed, &(k as u32).to_le_bytes()]).to_bytes();
+        let roll = u64::from_le_bytes(draw[..8].try_into().unwrap());
+        let pick = k + (roll % (DECK_SIZE as u64 - k as u64)) as usize;
+        deck.swap(k, pick);
+        hands[k / HAND_SIZE][k % HAND_SIZE] = deck[k];
+    }
+    
+    hands
+}
+
+/// Score a five-card hand; a higher score is a stronger hand.
+///
+/// The category sits above bit 20 and the ranks that break ties within the
+/// category fill the low 20 bits, four bits each, most significant first.
+pub fn evaluate_hand(cards: &[u8; HAND_SIZE]) -> u32 {
+    let mut counts = [0u8; 13];
+    for card in cards.iter() {
+        counts[(card % 13) as usize] += 1;
+    }
+    
+    // Ranks grouped by multiplicity, larger groups first, then higher ranks
+    let mut groups: Vec<(u8, u8)> = (0..13u8)
+        .rev()
+        .filter(|rank| counts[*rank as usize] > 0)
+        .map(|rank| (counts[rank as usize], rank))
+        .collect();
+    groups.sort_by_key(|group| Reverse(group.0));
+    
+    let flush = cards.iter().all(|card| card / 13 == cards[0] / 13);
+    let straight_high = if groups.len() == HAND_SIZE {
+        if groups[0].1 - groups[4].1 == 4 {
+            Some(groups[0].1)
+        } else if groups[0].1 == 12 && groups[1].1 == 3 {
+            Some(3) // Ace-low straight plays as five-high
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+    
+    let category = match (straight_high, flush, groups[0].0, groups.get(1).map(|g| g.0)) {
+        (Some(_), true, _, _) => STRAIGHT_FLUSH,
+        (_, _, 4, _) => FOUR_OF_A_KIND,
+        (_, _, 3, Some(2)) => FULL_HOUSE,
+        (_, true, _, _) => FLUSH,
+        (Some(_), _, _, _) => STRAIGHT,
+        (_, _, 3, _) => THREE_OF_A_KIND,
+        (_, _, 2, Some(2)) => TWO_PAIR,
+        (_, _, 2, _) => ONE_PAIR,
+        _ => HIGH_CARD,
+    };
+    
+    let kickers = match straight_high {
+        Some(high) if category == STRAIGHT || category == STRAIGHT_FLUSH => (high as u32) << 16,
+        _ => groups
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, (_, rank))| acc | (*rank as u32) << (16 - 4 * i)),
+    };
+    
+    (category as u32) << 20 | kickers
+}
+
+/// Hand category of a score produced by `evaluate_hand`
+pub fn hand_category(score: u32) -> u8 {
+    (score >> 20) as u8
+}
+
+/// Score the best five-card hand out of seven cards
+pub fn best_hand(cards: &[u8; 7]) -> u32 {
+    let mut best = 0;
+    // Every five-card hand leaves out exactly two of the seven cards
+    for skip_a in 0..7 {
+        for skip_b in skip_a + 1..7 {
+            let mut hand = [0u8; HAND_SIZE];
+            let mut next = 0;
+            for (i, card) in cards.iter().enumerate() {
+                if i != skip_a && i != skip_b {
+                    hand[next] = *card;
+                    next += 1;
+                }
+            }
+            best = best.max(evaluate_hand(&hand));
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // Card of `rank` (0 = two, 12 = ace) in `suit`
+    fn card(rank: u8, suit: u8) -> u8 {
+        suit * 13 + rank
+    }
+    
+    // Hand of the given ranks, spread over suits so it is never a flush
+    fn offsuit(ranks: [u8; HAND_SIZE]) -> [u8; HAND_SIZE] {
+        let mut hand = [0u8; HAND_SIZE];
+        for (i, rank) in ranks.iter().enumerate() {
+            hand[i] = card(*rank, (i % 4) as u8);
+        }
+        hand
+    }
+    
+    fn suited(ranks: [u8; HAND_SIZE], suit: u8) -> [u8; HAND_SIZE] {
+        ranks.map(|rank| card(rank, suit))
+    }
+    
+    #[test]
+    fn evaluate_hand_detects_every_category() {
+        let hands = [
+            (offsuit([12, 10, 7, 4, 2]), HIGH_CARD),
+            (offsuit([9, 9, 7, 4, 2]), ONE_PAIR),
+            (offsuit([9, 9, 7, 7, 2]), TWO_PAIR),
+            (offsuit([9, 9, 9, 4, 2]), THREE_OF_A_KIND),
+            (offsuit([8, 7, 6, 5, 4]), STRAIGHT),
+            (suited([12, 10, 7, 4, 2], 1), FLUSH),
+            (offsuit([9, 9, 9, 4, 4]), FULL_HOUSE),
+            (offsuit([9, 9, 9, 9, 4]), FOUR_OF_A_KIND),
+            (suited([8, 7, 6, 5, 4], 2), STRAIGHT_FLUSH),
+        ];
+        for (hand, category) in hands.iter() {
+            assert_eq!(hand_category(evaluate_hand(hand)), *category);
+        }
+        
+        // Each category beats every hand of the one below it
+        for pair in hands.windows(2) {
+            assert!(evaluate_hand(&pair[1].0) > evaluate_hand(&pair[0].0));
+        }
+    }
+    
+    #[test]
+    fn evaluate_hand_breaks_ties_on_ranks() {
+        // Higher pair, then the kickers in order
+        assert!(evaluate_hand(&offsuit([9, 9, 3, 2, 0])) > evaluate_hand(&offsuit([8, 8, 12, 11, 10])));
+        assert!(evaluate_hand(&offsuit([9, 9, 11, 2, 0])) > evaluate_hand(&offsuit([9, 9, 10, 8, 7])));
+        assert!(evaluate_hand(&offsuit([9, 9, 11, 2, 1])) > evaluate_hand(&offsuit([9, 9, 11, 2, 0])));
+        
+        // A full house is ranked by its three of a kind first
+        assert!(evaluate_hand(&offsuit([5, 5, 5, 0, 0])) > evaluate_hand(&offsuit([4, 4, 4, 12, 12])));
+        
+        // Suits never break a tie
+        assert_eq!(
+            evaluate_hand(&offsuit([12, 10, 7, 4, 2])),
+            evaluate_hand(&[card(12, 3), card(10, 2), card(7, 1), card(4, 0), card(2, 3)]),
+        );
+    }
+    
+    #[test]
+    fn ace_low_straight_plays_as_five_high() {
+        let wheel = evaluate_hand(&offsuit([12, 3, 2, 1, 0]));
+        let six_high = evaluate_hand(&offsuit([4, 3, 2, 1, 0]));
+        let ace_high = evaluate_hand(&offsuit([12, 11, 10, 9, 8]));
+        
+        assert_eq!(hand_category(wheel), STRAIGHT);
+        assert!(six_high > wheel);
+        assert!(ace_high > six_high);
+        
+        // Ace, king and three low cards do not wrap around
+        assert_eq!(hand_category(evaluate_hand(&offsuit([12, 11, 2, 1, 0]))), HIGH_CARD);
+    }
+    
+    #[test]
+    fn best_hand_picks_the_strongest_five_of_seven() {
+        // A flush on the board with a pair in the hand plays the flush
+        let flush_board = [
+            card(9, 0), card(9, 1),
+            card(12, 2), card(10, 2), card(7, 2), card(4, 2), card(1, 2),
+        ];
+        assert_eq!(best_hand(&flush_board), evaluate_hand(&suited([12, 10, 7, 4, 1], 2)));
+        
+        // Two three of a kinds make the best full house available
+        let two_trips = [
+            card(5, 0), card(5, 1), card(5, 2),
+            card(8, 0), card(8, 1), card(8, 2), card(0, 3),
+        ];
+        assert_eq!(best_hand(&two_trips), evaluate_hand(&offsuit([8, 8, 8, 5, 5])));
+        
+        // A six-card straight plays its top five
+        let long_straight = [
+            card(0, 0), card(1, 1), card(2, 2), card(3, 3), card(4, 0), card(5, 1), card(11, 2),
+        ];
+        assert_eq!(best_hand(&long_straight), evaluate_hand(&offsuit([5, 4, 3, 2, 1])));
+    }
+    
+    #[test]
+    fn best_hand_lets_the_board_play() {
+        // Both hole cards are below the board, so both players split
+        let board = [card(12, 0), card(11, 1), card(10, 2), card(9, 3), card(7, 0)];
+        let first = [card(0, 1), card(1, 2), board[0], board[1], board[2], board[3], board[4]];
+        let second = [card(0, 2), card(2, 3), board[0], board[1], board[2], board[3], board[4]];
+        assert_eq!(best_hand(&first), best_hand(&second));
+        
+        // A single hole card that fills the straight plays with four board cards
+        let straight = [card(8, 1), card(0, 2), board[0], board[1], board[2], board[3], board[4]];
+        assert_eq!(hand_category(best_hand(&straight)), STRAIGHT);
+        assert!(best_hand(&straight) > best_hand(&first));
+    }
+    
+    #[test]
+    fn deal_hands_never_repeats_a_card() {
+        let hands = deal_hands(&[7u8; 32], 10);
+        let mut seen = [false; DECK_CARDS];
+        for card in hands.iter().flatten() {
+            assert!((*card as usize) < DECK_CARDS);
+            assert!(!seen[*card as usize]);
+            seen[*card as usize] = true;
+        }
+        
+        // A seat's hand does not depend on how many seats are dealt
+        assert_eq!(deal_hands(&[7u8; 32], 2)[..], hands[..2]);
+    }
+}
@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use crate::state::GameType;
+use crate::constants::CONFIG_VERSION;
 
 #[account]
 pub struct ConfigurationAccount {
@@ -10,15 +12,17 @@ pub struct ConfigurationAccount {
     pub max_stake: u64,
     /// Maximum rounds for multi-round games
     pub max_rounds: u8,
-    /// Penalty percentage for folding (0-100)
+    /// Penalty bond for folding or timing out, as a percentage of the stake (0-100)
     pub fold_penalty: u8,
     /// Randomness method: 0=commit-reveal, 1=VRF, 2=VDF
     pub randomness_method: u8,
-    /// Platform fee percentage (0-100)
+    /// Legacy whole-percent platform fee, superseded by the per-game-type
+    /// basis-point fees below and only charged until the config is migrated
     pub platform_fee: u8,
     /// Prices for bonus items in lamports
     pub item_prices: Vec<u64>,
-    /// Timeout in seconds before games can be force-finished
+    /// Seconds a MultiRound seat without a per-game action clock has to act
+    /// before the game acts for it
     pub timeout: u64,
     /// Number of lottery tickets per stake unit
     pub ticket_conversion: u64,
@@ -26,8 +30,35 @@ pub struct ConfigurationAccount {
     pub game_counter: u64,
     /// Treasury address for platform fees
     pub treasury: Pubkey,
+    /// Who receives the fold penalty
+    pub fold_penalty_recipient: FoldPenaltyRecipient,
+    /// Legacy whole-percent cancel fee, superseded by `cancel_fee_bps`
+    pub cancel_fee: u8,
+    /// Platform fee on SimpleDuel payouts, in basis points
+    pub duel_fee_bps: u16,
+    /// Platform fee on MultiRound payouts, in basis points
+    pub multi_round_fee_bps: u16,
+    /// Platform fee on Lottery payouts, in basis points
+    pub lottery_fee_bps: u16,
+    /// Most platform fee taken from a single game in lamports (0 for no cap)
+    pub max_rake: u64,
+    /// Layout version of this account, 0 for configs from before versioning
+    pub config_version: u8,
+    /// Fee kept from each stake when players agree to cancel, in basis points
+    pub cancel_fee_bps: u16,
     /// Reserved for future use
-    pub reserved: [u8; 128],
+    pub reserved: [u8; 109],
+}
+
+/// Where a folding player's penalty goes.
+///
+/// A fold forfeits the player's stake to the pot either way; the penalty is
+/// the bond the player posted on top of their stake, so it never comes out
+/// of what the other players win.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FoldPenaltyRecipient {
+    Treasury, // Paid to the treasury
+    Opponent, // Added to the pot for the remaining players
 }
 
 impl ConfigurationAccount {
@@ -44,5 +75,114 @@ impl ConfigurationAccount {
         8 + // ticket_conversion
         8 + // game_counter
         32 + // treasury
-        128; // reserved
-}
\ No newline at end of file
+        1 + // fold_penalty_recipient
+        1 + // cancel_fee
+        2 + // duel_fee_bps
+        2 + // multi_round_fee_bps
+        2 + // lottery_fee_bps
+        8 + // max_rake
+        1 + // config_version
+        2 + // cancel_fee_bps
+        109; // reserved
+    
+    /// Platform fee rate for a game type, in basis points
+    pub fn fee_bps(&self, game_type: GameType) -> u16 {
+        // Configs not yet migrated read the basis-point fees as zero
+        if self.config_version == 0 {
+            return legacy_bps(self.platform_fee);
+        }
+        match game_type {
+            GameType::SimpleDuel => self.duel_fee_bps,
+            GameType::MultiRound => self.multi_round_fee_bps,
+            GameType::Lottery => self.lottery_fee_bps,
+        }
+    }
+    
+    /// Fee rate kept from refunds of an agreed cancellation, in basis points
+    pub fn cancel_fee_bps(&self) -> u16 {
+        if self.config_version == 0 {
+            return legacy_bps(self.cancel_fee);
+        }
+        self.cancel_fee_bps
+    }
+    
+    /// Bring a config written by an earlier program version up to date.
+    ///
+    /// The basis-point fees were carved out of the reserved bytes, so an
+    /// older config reads them as zero; they take over its whole-percent
+    /// fees instead. Returns whether anything changed.
+    pub fn migrate(&mut self) -> bool {
+        if self.config_version >= CONFIG_VERSION {
+            return false;
+        }
+        let fee_bps = legacy_bps(self.platform_fee);
+        self.duel_fee_bps = fee_bps;
+        self.multi_round_fee_bps = fee_bps;
+        self.lottery_fee_bps = fee_bps;
+        self.cancel_fee_bps = legacy_bps(self.cancel_fee);
+        self.config_version = CONFIG_VERSION;
+        true
+    }
+}
+
+// Whole-percent fee expressed in basis points
+fn legacy_bps(percent: u8) -> u16 {
+    percent as u16 * 100
+}
+
+/// A config written before versioning, reading its newer fields as zero
+#[cfg(test)]
+pub(crate) fn legacy_config(platform_fee: u8, cancel_fee: u8) -> ConfigurationAccount {
+    ConfigurationAccount {
+        admin: Pubkey::default(),
+        min_stake: 0,
+        max_stake: u64::MAX,
+        max_rounds: 0,
+        fold_penalty: 0,
+        randomness_method: 0,
+        platform_fee,
+        item_prices: Vec::new(),
+        timeout: 0,
+        ticket_conversion: 1,
+        game_counter: 0,
+        treasury: Pubkey::default(),
+        fold_penalty_recipient: FoldPenaltyRecipient::Treasury,
+        cancel_fee,
+        duel_fee_bps: 0,
+        multi_round_fee_bps: 0,
+        lottery_fee_bps: 0,
+        max_rake: 0,
+        config_version: 0,
+        cancel_fee_bps: 0,
+        reserved: [0; 109],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn unmigrated_configs_keep_charging_their_percentages() {
+        let config = legacy_config(3, 2);
+        assert_eq!(config.fee_bps(GameType::SimpleDuel), 300);
+        assert_eq!(config.fee_bps(GameType::Lottery), 300);
+        assert_eq!(config.cancel_fee_bps(), 200);
+    }
+    
+    #[test]
+    fn migrate_carries_percentages_over_once() {
+        let mut config = legacy_config(3, 2);
+        assert!(config.migrate());
+        assert_eq!(config.config_version, CONFIG_VERSION);
+        assert_eq!(config.duel_fee_bps, 300);
+        assert_eq!(config.multi_round_fee_bps, 300);
+        assert_eq!(config.lottery_fee_bps, 300);
+        assert_eq!(config.cancel_fee_bps(), 200);
+        
+        // Rates set after migrating are not overwritten by a second migration
+        config.duel_fee_bps = 150;
+        assert!(!config.migrate());
+        assert_eq!(config.fee_bps(GameType::SimpleDuel), 150);
+    }
+}
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::constants::DECK_PROOF_DEPTH;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 pub enum GameType {
@@ -25,6 +26,51 @@ pub enum GameMove {
     Heads,
     Tails,
     Number(u8),
+    Lizard,
+    Spock,
+}
+
+/// Ruleset used to score a game, chosen by the creator
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum GameVariant {
+    RockPaperScissors,                   // Classic three-throw duel
+    CoinFlip { creator_call: GameMove }, // Creator calls Heads or Tails
+    DiceBattle { dice_count: u8, sides: u8, tie_break: DiceTieBreak }, // Highest total wins
+    BattleRoyale { players: u8 },        // Elimination rock-paper-scissors, last survivor wins
+    RockPaperScissorsLizardSpock,        // Five-throw duel, draws one in five
+    Holdem,                              // Heads-up Texas Hold'em (MultiRound only, not yet open)
+}
+
+/// How a Dice Battle is settled when both totals are equal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DiceTieBreak {
+    Draw,       // Equal totals end in a draw
+    HighestDie, // Compare dice from highest to lowest
+    Reroll,     // Roll again from the same seed, up to DICE_MAX_REROLLS times
+}
+
+/// How a drawn SimpleDuel is settled
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DrawPolicy {
+    Refund,      // Each player gets their own stake back minus the platform fee
+    SplitPot,    // The pot minus the platform fee is shared equally
+    SuddenDeath, // A new commit round opens until someone wins
+}
+
+/// Who besides the creator may join a game
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum GameAccess {
+    Public,                             // Anyone who knows the game can join
+    Invite { player: Pubkey },          // Direct challenge to one player
+    AccessCode { code_hash: [u8; 32] }, // Joiners present the code behind the hash
+}
+
+/// How raises are sized in a MultiRound game
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BettingStructure {
+    FixedLimit, // Raises are exactly one bet unit, capped per round
+    PotLimit,   // Raises may not exceed the pot after calling
+    NoLimit,    // Any raise of at least the previous raise increment
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
@@ -33,6 +79,15 @@ pub enum BetAction {
     Call,
     Raise(u64),
     Fold,
+    AllIn,
+}
+
+/// One opened entry of a Hold'em deck permutation, with its Merkle proof
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct CardOpening {
+    pub value: u8,
+    pub salt: [u8; 32],
+    pub proof: [[u8; 32]; DECK_PROOF_DEPTH],
 }
 
 #[account]
@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
-use crate::constants::MAX_PLAYERS;
-use super::game::{GameType, GameState};
+use crate::constants::{
+    MAX_PLAYERS, MAX_TABLE_SEATS, HOLDEM_SEATS, HOLDEM_DECK_SLOTS, HOLDEM_BOARD_CARDS, DECK_CARDS,
+};
+use super::game::{GameType, GameState, GameMove, GameVariant, DiceTieBreak, DrawPolicy,
+    BettingStructure, GameAccess};
 
 /// Optimized game account with fixed arrays for better rent efficiency
 /// Uses zero-copy to avoid stack overflow issues
@@ -65,6 +68,103 @@ pub struct GameAccountOptimized {
     
     /// Flags for various boolean states (bit-packed)
     pub flags: u8, // bit 0: is_resolved, bit 1: fees_distributed, bit 2: uses_vrf, etc.
+    
+    /// Ruleset variant (0=rock-paper-scissors, 1=coin flip, 2=dice battle, 3=battle royale,
+    /// 4=rock-paper-scissors-lizard-Spock, 5=hold'em)
+    pub variant: u8,
+    
+    /// Variant-specific parameters
+    /// (coin flip: creator's call as a packed move; dice: count, sides, tie-break;
+    /// battle royale: player count)
+    pub variant_params: [u8; 3],
+    
+    /// Rounds won by each duelist in a best-of-N series
+    pub series_wins: [u8; 2],
+    
+    /// Draw settlement policy (0=refund, 1=split pot, 2=sudden death)
+    pub draw_policy: u8,
+    
+    /// Deadline for the current commit or reveal phase (unix timestamp)
+    pub phase_deadline: u32,
+    
+    /// Forfeited bonds owed to the treasury, paid out with the first claim
+    pub penalty_amount: u64,
+    
+    /// Players knocked out of a battle royale or a showdown (bit per player index)
+    pub eliminated_mask: u16,
+    
+    /// Seat whose turn it is in the current betting round
+    pub turn_index: u8,
+    
+    /// Players who have acted since the last bet or raise (bit per player index)
+    pub acted_mask: u16,
+    
+    /// Smallest raise increment allowed in the current betting round
+    pub min_raise: u64,
+    
+    /// Hold'em entries of the first seat's deck opened for the board, by deck slot
+    pub deck_openings: [u8; HOLDEM_DECK_SLOTS],
+    
+    /// Hold'em deck slots the first seat has opened (bit per slot)
+    pub opened_mask: u16,
+    
+    /// Hold'em deck permutations revealed at showdown, one per seat
+    pub deck_reveals: [[u8; DECK_CARDS]; HOLDEM_SEATS],
+    
+    /// Hold'em community cards dealt so far
+    pub board: [u8; HOLDEM_BOARD_CARDS],
+    pub board_count: u8,
+    
+    /// MultiRound betting structure (0=no-limit, 1=fixed-limit, 2=pot-limit)
+    pub betting_structure: u8,
+    
+    /// Bets and raises made in the current betting round
+    pub round_raises: u8,
+    
+    /// MultiRound players who are all-in (bit per player index)
+    pub all_in_mask: u16,
+    
+    /// MultiRound winnings per seat from the main and side pots, before fees
+    pub payouts: [u64; MAX_TABLE_SEATS],
+    
+    /// Seat cap: players in a duel or at a table, ticket entries in a lottery
+    pub max_players: u8,
+    
+    /// MultiRound dealer button seat
+    pub dealer_index: u8,
+    
+    /// MultiRound players who have folded (bit per player index)
+    pub folded_mask: u16,
+    
+    /// Seconds a MultiRound player has to act before anyone can act for them (0=no clock)
+    pub action_timeout: u8,
+    
+    /// Join restriction (0=public, 1=invite, 2=access code)
+    pub access: u8,
+    
+    /// Invited player or access code hash, per `access`
+    pub access_key: [u8; 32],
+    
+    /// Completed game this one is a rematch of (default if none)
+    pub rematch_of: Pubkey,
+    
+    /// Players who have agreed to cancel the game (bit per player index)
+    pub cancel_offers: u16,
+    
+    /// MultiRound seats that have claimed their payout (bit per player index)
+    pub claimed_mask: u16,
+    
+    /// Bump of the vault PDA derived from this game's key
+    pub vault_bump: u8,
+    
+    /// Bond each player posts on top of their stake, forfeited on a fold or timeout
+    pub penalty_bond: u64,
+    
+    /// Players whose bond was forfeited or returned (bit per player index)
+    pub bond_mask: u16,
+    
+    /// Rematch proposed from this game once it completed (default if none)
+    pub rematched_as: Pubkey,
 }
 
 impl GameAccountOptimized {
@@ -89,7 +189,39 @@ impl GameAccountOptimized {
         8 + // platform_fee_collected
         32 + // treasury
         1 + // flags
-        20; // padding for alignment
+        1 + // variant
+        3 + // variant_params
+        2 + // series_wins
+        1 + // draw_policy
+        4 + // phase_deadline
+        8 + // penalty_amount
+        2 + // eliminated_mask
+        1 + // turn_index
+        2 + // acted_mask
+        8 + // min_raise
+        HOLDEM_DECK_SLOTS + // deck_openings
+        2 + // opened_mask
+        (DECK_CARDS * HOLDEM_SEATS) + // deck_reveals
+        HOLDEM_BOARD_CARDS + // board
+        1 + // board_count
+        1 + // betting_structure
+        1 + // round_raises
+        2 + // all_in_mask
+        (8 * MAX_TABLE_SEATS) + // payouts
+        1 + // max_players
+        1 + // dealer_index
+        2 + // folded_mask
+        1 + // action_timeout
+        1 + // access
+        32 + // access_key
+        32 + // rematch_of
+        2 + // cancel_offers
+        2 + // claimed_mask
+        1 + // vault_bump
+        8 + // penalty_bond
+        2 + // bond_mask
+        32 + // rematched_as
+        34; // padding for alignment
     
     /// Unpack game type from packed byte
     pub fn game_type(&self) -> GameType {
@@ -130,6 +262,135 @@ impl GameAccountOptimized {
         self.game_type_and_state = type_bits | (state_bits << 4);
     }
     
+    /// Unpack ruleset variant and its parameters
+    pub fn variant(&self) -> GameVariant {
+        match self.variant {
+            1 => GameVariant::CoinFlip {
+                creator_call: if self.variant_params[0] == 5 {
+                    GameMove::Tails
+                } else {
+                    GameMove::Heads
+                },
+            },
+            2 => GameVariant::DiceBattle {
+                dice_count: self.variant_params[0],
+                sides: self.variant_params[1],
+                tie_break: match self.variant_params[2] {
+                    1 => DiceTieBreak::HighestDie,
+                    2 => DiceTieBreak::Reroll,
+                    _ => DiceTieBreak::Draw,
+                },
+            },
+            3 => GameVariant::BattleRoyale {
+                players: self.variant_params[0],
+            },
+            4 => GameVariant::RockPaperScissorsLizardSpock,
+            5 => GameVariant::Holdem,
+            _ => GameVariant::RockPaperScissors,
+        }
+    }
+    
+    /// Pack ruleset variant and its parameters
+    pub fn set_variant(&mut self, variant: GameVariant) {
+        self.variant_params = [0; 3];
+        self.variant = match variant {
+            GameVariant::RockPaperScissors => 0,
+            GameVariant::CoinFlip { creator_call } => {
+                self.variant_params[0] = if creator_call == GameMove::Tails { 5 } else { 4 };
+                1
+            }
+            GameVariant::DiceBattle { dice_count, sides, tie_break } => {
+                self.variant_params = [
+                    dice_count,
+                    sides,
+                    match tie_break {
+                        DiceTieBreak::Draw => 0,
+                        DiceTieBreak::HighestDie => 1,
+                        DiceTieBreak::Reroll => 2,
+                    },
+                ];
+                2
+            }
+            GameVariant::BattleRoyale { players } => {
+                self.variant_params[0] = players;
+                3
+            }
+            GameVariant::RockPaperScissorsLizardSpock => 4,
+            GameVariant::Holdem => 5,
+        };
+    }
+    
+    /// Check whether a battle-royale player has been knocked out
+    pub fn is_eliminated(&self, index: usize) -> bool {
+        index < 16 && (self.eliminated_mask & (1 << index)) != 0
+    }
+    
+    /// Knock a battle-royale player out
+    pub fn set_eliminated(&mut self, index: usize) {
+        self.eliminated_mask |= 1 << index;
+    }
+    
+    /// Number of players still in contention
+    pub fn survivor_count(&self) -> u8 {
+        (0..self.player_count as usize)
+            .filter(|i| !self.is_eliminated(*i))
+            .count() as u8
+    }
+    
+    /// Unpack draw settlement policy
+    pub fn draw_policy(&self) -> DrawPolicy {
+        match self.draw_policy {
+            1 => DrawPolicy::SplitPot,
+            2 => DrawPolicy::SuddenDeath,
+            _ => DrawPolicy::Refund,
+        }
+    }
+    
+    /// Pack draw settlement policy
+    pub fn set_draw_policy(&mut self, policy: DrawPolicy) {
+        self.draw_policy = match policy {
+            DrawPolicy::Refund => 0,
+            DrawPolicy::SplitPot => 1,
+            DrawPolicy::SuddenDeath => 2,
+        };
+    }
+    
+    /// Unpack MultiRound betting structure
+    pub fn betting_structure(&self) -> BettingStructure {
+        match self.betting_structure {
+            1 => BettingStructure::FixedLimit,
+            2 => BettingStructure::PotLimit,
+            _ => BettingStructure::NoLimit,
+        }
+    }
+    
+    /// Pack MultiRound betting structure
+    pub fn set_betting_structure(&mut self, structure: BettingStructure) {
+        self.betting_structure = match structure {
+            BettingStructure::NoLimit => 0,
+            BettingStructure::FixedLimit => 1,
+            BettingStructure::PotLimit => 2,
+        };
+    }
+    
+    /// Unpack join restriction
+    pub fn access(&self) -> GameAccess {
+        match self.access {
+            1 => GameAccess::Invite { player: Pubkey::new_from_array(self.access_key) },
+            2 => GameAccess::AccessCode { code_hash: self.access_key },
+            _ => GameAccess::Public,
+        }
+    }
+    
+    /// Pack join restriction
+    pub fn set_access(&mut self, access: GameAccess) {
+        (self.access, self.access_key) = match access {
+            GameAccess::Public => (0, [0; 32]),
+            GameAccess::Invite { player } => (1, player.to_bytes()),
+            GameAccess::AccessCode { code_hash } => (2, code_hash),
+        };
+    }
+    
     /// Get current round from packed byte
     pub fn current_round(&self) -> u8 {
         self.rounds & 0x0F
@@ -183,4 +444,7 @@ pub const FLAG_IS_RESOLVED: u8 = 0;
 pub const FLAG_FEES_DISTRIBUTED: u8 = 1;
 pub const FLAG_USES_VRF: u8 = 2;
 pub const FLAG_HAS_TIMEOUT: u8 = 3;
-pub const FLAG_AUTO_RESOLVE: u8 = 4;
\ No newline at end of file
+pub const FLAG_AUTO_RESOLVE: u8 = 4;
+pub const FLAG_IS_DRAW: u8 = 5;
+pub const FLAG_WINNINGS_CLAIMED: u8 = 6;
+pub const FLAG_MUTUAL_CANCEL: u8 = 7;
\ No newline at end of file
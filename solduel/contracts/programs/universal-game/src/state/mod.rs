@@ -6,7 +6,10 @@ pub mod player;
 pub use config::*;
 pub use player::*;
 // Export enums from game module
-pub use game::{GameType, GameState, GameMove, BetAction};
+pub use game::{
+    GameType, GameState, GameMove, GameVariant, DiceTieBreak, DrawPolicy, BettingStructure,
+    BetAction, GameAccess, CardOpening,
+};
 // Use optimized game structure
 pub use game_optimized::{
     GameAccountOptimized,
@@ -15,4 +18,7 @@ pub use game_optimized::{
     FLAG_USES_VRF,
     FLAG_HAS_TIMEOUT,
     FLAG_AUTO_RESOLVE,
+    FLAG_IS_DRAW,
+    FLAG_WINNINGS_CLAIMED,
+    FLAG_MUTUAL_CANCEL,
 };
\ No newline at end of file
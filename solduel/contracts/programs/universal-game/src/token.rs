@@ -4,7 +4,8 @@ use anchor_spl::{
     token::{self, Mint, Token, TokenAccount, Transfer},
 };
 use crate::state::GameAccountOptimized;
-use crate::errors::TokenError;
+use crate::errors::{TokenError, TreasuryError};
+use crate::instructions::treasury::calculate_winner_payout;
 
 /// Token configuration for game
 #[account]
@@ -98,6 +99,7 @@ pub fn distribute_token_winnings<'info>(
     // Get game values before mutable borrow
     let game = ctx.accounts.game.load()?;
     let pot_total = game.pot_total;
+    let game_type = game.game_type();
     let game_creator = game.creator;
     let game_id = game.game_id;
     drop(game); // Release immutable borrow
@@ -113,8 +115,7 @@ pub fn distribute_token_winnings<'info>(
     );
     
     // Calculate fees and winner payout
-    let platform_fee = (pot_total * config.platform_fee as u64) / 100;
-    let winner_payout = pot_total - platform_fee;
+    let (winner_payout, platform_fee) = calculate_winner_payout(config, game_type, pot_total);
     
     // Get game account info for PDA signing
     let game_account_info = ctx.accounts.game.to_account_info();
@@ -312,6 +313,7 @@ pub struct DistributeTokenWinnings<'info> {
     pub winner_token_account: Account<'info, TokenAccount>,
     
     /// CHECK: Treasury pubkey from config
+    #[account(address = config.treasury @ TreasuryError::InvalidTreasury)]
     pub treasury: AccountInfo<'info>,
     
     #[account(
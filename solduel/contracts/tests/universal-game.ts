@@ -49,7 +49,7 @@ describe("universal-game", () => {
       console.log("Config initialized with:");
       console.log("  Min stake:", configData.minStake.toString());
       console.log("  Max stake:", configData.maxStake.toString());
-      console.log("  Platform fee:", configData.platformFee, "%");
+      console.log("  Duel fee:", configData.duelFeeBps, "bps");
     } catch (error) {
       // Config might already be initialized, which is fine
       console.log("Config may already be initialized");
@@ -88,7 +88,7 @@ describe("universal-game", () => {
     const [vaultPDA] = PublicKey.findProgramAddressSync(
       [
         Buffer.from("vault"),
-        gameId.toArrayLike(Buffer, "le", 8),
+        gamePDA.toBuffer(),
       ],
       program.programId
     );
@@ -98,7 +98,14 @@ describe("universal-game", () => {
       .createGame(
         { simpleDuel: {} }, // GameType enum
         new anchor.BN(100000000), // stake: 0.1 SOL (matches minimum)
-        null // max_players (optional)
+        null, // max_players (optional)
+        null, // variant (defaults to rock-paper-scissors)
+        null, // best_of (defaults to a single round)
+        null, // draw_policy (defaults to refund)
+        null, // hand_commitment (MultiRound only)
+        null, // betting_structure (MultiRound only, defaults to no-limit)
+        null, // action_timeout (MultiRound only, defaults to no clock)
+        null // access (defaults to public)
       )
       .accounts({
         game: gamePDA,
@@ -32,7 +32,20 @@ export enum GameMove {
   Paper = 'Paper',
   Scissors = 'Scissors',
   Heads = 'Heads',
-  Tails = 'Tails'
+  Tails = 'Tails',
+  Lizard = 'Lizard',
+  Spock = 'Spock'
+}
+
+// A duel move: a named throw, or a number for dice battles
+export type DuelMove = GameMove | { number: number };
+
+// Largest number a move can carry, matching the program's MAX_MOVE_NUMBER
+const MAX_MOVE_NUMBER = 247;
+
+/** Number move for a dice battle */
+export function numberMove(n: number): DuelMove {
+  return { number: n };
 }
 
 // Betting Actions
@@ -40,7 +53,14 @@ export enum BetAction {
   Check = 'Check',
   Call = 'Call',
   Raise = 'Raise',
-  Fold = 'Fold'
+  Fold = 'Fold',
+  AllIn = 'AllIn'
+}
+
+// Who receives a forfeited penalty bond
+export enum FoldPenaltyRecipient {
+  Treasury = 'Treasury',
+  Opponent = 'Opponent'
 }
 
 // Strategic Actions for multi-round duels
@@ -48,7 +68,8 @@ export enum StrategicAction {
   Check = 'Check',
   Call = 'Call',
   Raise = 'Raise',
-  Fold = 'Fold'
+  Fold = 'Fold',
+  AllIn = 'AllIn'
 }
 
 // Duel State for strategic games
@@ -89,6 +110,97 @@ export interface PlayerStats {
   bestStreak: number;
 }
 
+// Optional create_game arguments, as Anchor-encoded values
+export interface CreateGameOptions {
+  variant?: any;
+  bestOf?: number;
+  drawPolicy?: any;
+  handCommitment?: Buffer;
+  bettingStructure?: any;
+  actionTimeout?: number;
+  access?: any;
+}
+
+// Optional join_game arguments
+export interface JoinGameOptions {
+  handCommitment?: Buffer;
+  accessCode?: Buffer;
+}
+
+// Domain tags, matching the program's commitment module
+const MOVE_COMMITMENT_DOMAIN = Buffer.from('solduel:move:v1');
+const HAND_COMMITMENT_DOMAIN = Buffer.from('solduel:hand:v1');
+
+// Borsh encoding of a GameMove: the variant index, followed by the value
+// for a number move
+function encodeGameMove(move: DuelMove): Buffer {
+  if (typeof move === 'object') {
+    return Buffer.from([6, checkedMoveNumber(move.number)]);
+  }
+  const indices = {
+    [GameMove.None]: 0,
+    [GameMove.Rock]: 1,
+    [GameMove.Paper]: 2,
+    [GameMove.Scissors]: 3,
+    [GameMove.Heads]: 4,
+    [GameMove.Tails]: 5,
+    [GameMove.Lizard]: 7,
+    [GameMove.Spock]: 8,
+  };
+  return Buffer.from([indices[move]]);
+}
+
+// The program rejects numbers it cannot pack, so reject them before committing
+function checkedMoveNumber(n: number): number {
+  if (!Number.isInteger(n) || n < 0 || n > MAX_MOVE_NUMBER) {
+    throw new Error(`Move number must be an integer from 0 to ${MAX_MOVE_NUMBER}`);
+  }
+  return n;
+}
+
+/**
+ * Canonical commitment for a duel move, matching `compute_move_commitment`
+ * in the program. It binds the program, game, round and player, so a
+ * commitment copied from another player cannot be revealed.
+ */
+export function computeMoveCommitment(
+  programId: PublicKey,
+  gameId: BN,
+  round: number,
+  player: PublicKey,
+  move: DuelMove,
+  nonce: Buffer
+): Buffer {
+  return crypto.createHash('sha256')
+    .update(MOVE_COMMITMENT_DOMAIN)
+    .update(programId.toBuffer())
+    .update(gameId.toArrayLike(Buffer, 'le', 8))
+    .update(Buffer.from([round]))
+    .update(player.toBuffer())
+    .update(encodeGameMove(move))
+    .update(nonce)
+    .digest();
+}
+
+/**
+ * Commitment a MultiRound player submits when taking a seat, matching
+ * `compute_hand_commitment` in the program. The secret is revealed at showdown.
+ */
+export function computeHandCommitment(
+  programId: PublicKey,
+  gameId: BN,
+  player: PublicKey,
+  secret: Buffer
+): Buffer {
+  return crypto.createHash('sha256')
+    .update(HAND_COMMITMENT_DOMAIN)
+    .update(programId.toBuffer())
+    .update(gameId.toArrayLike(Buffer, 'le', 8))
+    .update(player.toBuffer())
+    .update(secret)
+    .digest();
+}
+
 export class SolDuelSDK {
   private connection: Connection;
   private provider: AnchorProvider;
@@ -131,8 +243,14 @@ export class SolDuelSDK {
   async updateConfig(params: {
     minStake?: number;
     maxStake?: number;
-    platformFee?: number;
+    duelFeeBps?: number;
+    multiRoundFeeBps?: number;
+    lotteryFeeBps?: number;
+    maxRake?: number;
     timeout?: number;
+    foldPenalty?: number;
+    foldPenaltyRecipient?: FoldPenaltyRecipient;
+    cancelFeeBps?: number;
   }): Promise<string> {
     const [configPDA] = this.getConfigPDA();
     
@@ -140,8 +258,14 @@ export class SolDuelSDK {
       .updateConfig(
         params.minStake ? new BN(params.minStake * LAMPORTS_PER_SOL) : null,
         params.maxStake ? new BN(params.maxStake * LAMPORTS_PER_SOL) : null,
-        params.platformFee || null,
-        params.timeout ? new BN(params.timeout) : null
+        params.duelFeeBps ?? null,
+        params.multiRoundFeeBps ?? null,
+        params.lotteryFeeBps ?? null,
+        params.maxRake !== undefined ? new BN(params.maxRake * LAMPORTS_PER_SOL) : null,
+        params.timeout ? new BN(params.timeout) : null,
+        params.foldPenalty ?? null,
+        params.foldPenaltyRecipient ? this.mapFoldPenaltyRecipient(params.foldPenaltyRecipient) : null,
+        params.cancelFeeBps ?? null
       )
       .accounts({
         config: configPDA,
@@ -152,25 +276,51 @@ export class SolDuelSDK {
     return tx;
   }
 
+  async migrateConfig(): Promise<string> {
+    const [configPDA] = this.getConfigPDA();
+    
+    const tx = await this.program.methods
+      .migrateConfig()
+      .accounts({
+        config: configPDA,
+        admin: this.playerWallet,
+      })
+      .rpc();
+    
+    return tx;
+  }
+
   // ===== GAME LIFECYCLE =====
 
   async createGame(
     gameType: GameType,
     stakeAmount: number,
-    maxPlayers?: number
+    maxPlayers?: number,
+    options: CreateGameOptions = {}
   ): Promise<{ gameId: string; tx: string }> {
     const [configPDA] = this.getConfigPDA();
     const config = await this.program.account.configurationAccount.fetch(configPDA);
     const gameId = config.gameCounter;
     
     const [gamePDA] = this.getGamePDA(gameId, this.playerWallet);
-    const [vaultPDA] = this.getVaultPDA(gameId);
+    const [vaultPDA] = this.getVaultPDA(gamePDA);
     
     const gameTypeAnchor = this.mapGameType(gameType);
     const stakeAmountBN = new BN(stakeAmount * LAMPORTS_PER_SOL);
     
     const tx = await this.program.methods
-      .createGame(gameTypeAnchor, stakeAmountBN, maxPlayers || null)
+      .createGame(
+        gameTypeAnchor,
+        stakeAmountBN,
+        maxPlayers || null,
+        options.variant ?? null,
+        options.bestOf ?? null,
+        options.drawPolicy ?? null,
+        options.handCommitment ? Array.from(options.handCommitment) : null,
+        options.bettingStructure ?? null,
+        options.actionTimeout ?? null,
+        options.access ?? null
+      )
       .accounts({
         game: gamePDA,
         config: configPDA,
@@ -183,17 +333,20 @@ export class SolDuelSDK {
     return { gameId: gameId.toString(), tx };
   }
 
-  async joinGame(gameId: string): Promise<string> {
+  async joinGame(gameId: string, options: JoinGameOptions = {}): Promise<string> {
     const gameIdBN = new BN(gameId);
     const game = await this.getGame(gameId);
     
     if (!game) throw new Error('Game not found');
     
     const [gamePDA] = this.getGamePDA(gameIdBN, game.creator);
-    const [vaultPDA] = this.getVaultPDA(gameIdBN);
+    const [vaultPDA] = this.getVaultPDA(gamePDA);
     
     const tx = await this.program.methods
-      .joinGame()
+      .joinGame(
+        options.handCommitment ? Array.from(options.handCommitment) : null,
+        options.accessCode ? Array.from(options.accessCode) : null
+      )
       .accounts({
         game: gamePDA,
         vault: vaultPDA,
@@ -228,7 +381,7 @@ export class SolDuelSDK {
 
   async commitMove(
     gameId: string,
-    move: GameMove
+    move: DuelMove
   ): Promise<{ tx: string; nonce: string }> {
     const gameIdBN = new BN(gameId);
     const game = await this.getGame(gameId);
@@ -237,11 +390,16 @@ export class SolDuelSDK {
     
     const [gamePDA] = this.getGamePDA(gameIdBN, game.creator);
     
-    // Generate nonce and hash
+    // Generate nonce and the commitment for this round
     const nonce = crypto.randomBytes(32);
-    const moveBytes = Buffer.from([this.getMoveIndex(move)]);
-    const dataToHash = Buffer.concat([moveBytes, nonce]);
-    const moveHash = crypto.createHash('sha256').update(dataToHash).digest();
+    const moveHash = computeMoveCommitment(
+      this.program.programId,
+      gameIdBN,
+      game.currentRound,
+      this.playerWallet,
+      move,
+      nonce
+    );
     
     const tx = await this.program.methods
       .commitMove(Array.from(moveHash))
@@ -256,7 +414,7 @@ export class SolDuelSDK {
 
   async revealMove(
     gameId: string,
-    move: GameMove,
+    move: DuelMove,
     nonce: string
   ): Promise<string> {
     const gameIdBN = new BN(gameId);
@@ -281,9 +439,38 @@ export class SolDuelSDK {
 
   // ===== STRATEGIC DUEL FUNCTIONS =====
   
-  async createStrategicDuel(stakeAmount: number): Promise<{ duelId: string; tx: string }> {
-    // Create a multi-round game for strategic duels
-    return this.createGame(GameType.MultiRound, stakeAmount, 2);
+  // Strategic duels are MultiRound games, so each seat commits to a hand
+  // secret that must be kept to reveal at showdown
+  async createStrategicDuel(
+    stakeAmount: number
+  ): Promise<{ duelId: string; tx: string; handSecret: string }> {
+    const [configPDA] = this.getConfigPDA();
+    const config = await this.program.account.configurationAccount.fetch(configPDA);
+    const handSecret = crypto.randomBytes(32);
+    const handCommitment = computeHandCommitment(
+      this.program.programId,
+      config.gameCounter,
+      this.playerWallet,
+      handSecret
+    );
+    
+    const { gameId, tx } = await this.createGame(GameType.MultiRound, stakeAmount, 2, {
+      handCommitment,
+    });
+    return { duelId: gameId, tx, handSecret: handSecret.toString('hex') };
+  }
+
+  async joinStrategicDuel(duelId: string): Promise<{ tx: string; handSecret: string }> {
+    const handSecret = crypto.randomBytes(32);
+    const handCommitment = computeHandCommitment(
+      this.program.programId,
+      new BN(duelId),
+      this.playerWallet,
+      handSecret
+    );
+    
+    const tx = await this.joinGame(duelId, { handCommitment });
+    return { tx, handSecret: handSecret.toString('hex') };
   }
 
   async getDuelState(duelId: string): Promise<any> {
@@ -338,7 +525,7 @@ export class SolDuelSDK {
     if (!game) throw new Error('Game not found');
     
     const [gamePDA] = this.getGamePDA(gameIdBN, game.creator);
-    const [vaultPDA] = this.getVaultPDA(gameIdBN);
+    const [vaultPDA] = this.getVaultPDA(gamePDA);
     
     let actionAnchor: any;
     if (action === BetAction.Raise && raiseAmount) {
@@ -369,7 +556,7 @@ export class SolDuelSDK {
     if (!game) throw new Error('Game not found');
     
     const [gamePDA] = this.getGamePDA(gameIdBN, game.creator);
-    const [vaultPDA] = this.getVaultPDA(gameIdBN);
+    const [vaultPDA] = this.getVaultPDA(gamePDA);
     
     const tx = await this.program.methods
       .enterLottery(numTickets)
@@ -413,7 +600,7 @@ export class SolDuelSDK {
     
     const [gamePDA] = this.getGamePDA(gameIdBN, game.creator);
     const [configPDA] = this.getConfigPDA();
-    const [vaultPDA] = this.getVaultPDA(gameIdBN);
+    const [vaultPDA] = this.getVaultPDA(gamePDA);
     
     const config = await this.program.account.configurationAccount.fetch(configPDA);
     
@@ -431,6 +618,28 @@ export class SolDuelSDK {
     return tx;
   }
 
+  async claimBond(gameId: string): Promise<string> {
+    const gameIdBN = new BN(gameId);
+    const game = await this.getGame(gameId);
+    
+    if (!game) throw new Error('Game not found');
+    
+    const [gamePDA] = this.getGamePDA(gameIdBN, game.creator);
+    const [vaultPDA] = this.getVaultPDA(gamePDA);
+    
+    const tx = await this.program.methods
+      .claimBond()
+      .accounts({
+        game: gamePDA,
+        vault: vaultPDA,
+        player: this.playerWallet,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    
+    return tx;
+  }
+
   async resolveGame(gameId: string): Promise<string> {
     const gameIdBN = new BN(gameId);
     const game = await this.getGame(gameId);
@@ -438,11 +647,13 @@ export class SolDuelSDK {
     if (!game) throw new Error('Game not found');
     
     const [gamePDA] = this.getGamePDA(gameIdBN, game.creator);
+    const [configPDA] = this.getConfigPDA();
     
     const tx = await this.program.methods
       .resolveGame()
       .accounts({
         game: gamePDA,
+        config: configPDA,
         player: this.playerWallet,
       })
       .rpc();
@@ -525,11 +736,11 @@ export class SolDuelSDK {
     );
   }
 
-  private getVaultPDA(gameId: BN): [PublicKey, number] {
+  private getVaultPDA(game: PublicKey): [PublicKey, number] {
     return PublicKey.findProgramAddressSync(
       [
         Buffer.from('vault'),
-        gameId.toArrayLike(Buffer, 'le', 8)
+        game.toBuffer()
       ],
       this.program.programId
     );
@@ -595,7 +806,10 @@ export class SolDuelSDK {
     return mapping[type];
   }
 
-  private mapGameMove(move: GameMove): any {
+  private mapGameMove(move: DuelMove): any {
+    if (typeof move === 'object') {
+      return { number: [checkedMoveNumber(move.number)] };
+    }
     const mapping = {
       [GameMove.None]: { none: {} },
       [GameMove.Rock]: { rock: {} },
@@ -603,30 +817,29 @@ export class SolDuelSDK {
       [GameMove.Scissors]: { scissors: {} },
       [GameMove.Heads]: { heads: {} },
       [GameMove.Tails]: { tails: {} },
+      [GameMove.Lizard]: { lizard: {} },
+      [GameMove.Spock]: { spock: {} },
     };
     return mapping[move];
   }
 
+  private mapFoldPenaltyRecipient(recipient: FoldPenaltyRecipient): any {
+    const mapping = {
+      [FoldPenaltyRecipient.Treasury]: { treasury: {} },
+      [FoldPenaltyRecipient.Opponent]: { opponent: {} },
+    };
+    return mapping[recipient];
+  }
+
   private mapBetAction(action: BetAction): any {
     const mapping = {
       [BetAction.Check]: { check: {} },
       [BetAction.Call]: { call: {} },
       [BetAction.Fold]: { fold: {} },
+      [BetAction.AllIn]: { allIn: {} },
     };
     return mapping[action as keyof typeof mapping] || { check: {} };
   }
-
-  private getMoveIndex(move: GameMove): number {
-    const indices = {
-      [GameMove.None]: 0,
-      [GameMove.Rock]: 1,
-      [GameMove.Paper]: 2,
-      [GameMove.Scissors]: 3,
-      [GameMove.Heads]: 4,
-      [GameMove.Tails]: 5,
-    };
-    return indices[move] || 0;
-  }
 }
 
 export default SolDuelSDK;
\ No newline at end of file